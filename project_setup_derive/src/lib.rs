@@ -1,160 +1,599 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use std::collections::HashMap;
-use syn::{DeriveInput, LitStr, Meta, Token, parse_macro_input, parse_str};
-#[proc_macro_derive(LoopableNumberedEnum, attributes(numbered_enum))]
-pub fn numbered_enum_derive(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-    let mut loop_within: Option<usize> = None;
-    for attr in &input.attrs {
-        if attr.path().is_ident("numbered_enum") {
-            let _ = attr.parse_nested_meta(|meta| {
-                if meta.path.is_ident("loop_within") {
-                    let value = meta.value()?;
-                    let lit: syn::LitInt = value.parse()?;
-                    loop_within = lit.base10_parse().ok();
-                }
-                Ok(())
-            });
+use quote::{ToTokens, format_ident, quote, quote_spanned};
+use syn::{DeriveInput, Error, Ident, LitStr, Meta, Token, parse_macro_input};
+/// The variants of an enum, or an error pointing at `name` for anything else.
+fn enum_variants<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<&'a syn::punctuated::Punctuated<syn::Variant, Token![,]>> {
+    match &input.data {
+        syn::Data::Enum(data) => Ok(&data.variants),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            format!("{derive} can only be derived for enums"),
+        )),
+    }
+}
+fn numbered_enum(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let variants = enum_variants(input, "LoopableNumberedEnum")?;
+    if let Some(attr) = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("numbered_enum"))
+    {
+        return Err(Error::new_spanned(
+            attr,
+            "the variant count is inferred, use #[numbered_enum(skip)] on variants to leave them \
+             out of the cycle",
+        ));
+    }
+    let mut idents = vec![];
+    let mut cycle = vec![];
+    for variant in variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(Error::new_spanned(
+                &variant.fields,
+                "LoopableNumberedEnum variants cannot have fields",
+            ));
+        }
+        let mut skip = false;
+        for attr in &variant.attrs {
+            if attr.path().is_ident("numbered_enum") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("skip") {
+                        skip = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `skip`"))
+                    }
+                })?;
+            }
+        }
+        if !skip {
+            cycle.push(idents.len());
         }
+        idents.push(&variant.ident);
     }
-    let expanded = quote! {
+    if cycle.is_empty() {
+        return Err(Error::new_spanned(
+            name,
+            "LoopableNumberedEnum needs at least one variant which is not skipped",
+        ));
+    }
+    // 跳过的变体也能前进/后退, 落到声明顺序上相邻的未跳过变体
+    let step = |forward: bool| {
+        let arms = (0 .. idents.len()).map(|index| {
+            let target = if forward {
+                cycle
+                    .iter()
+                    .find(|&&position| position > index)
+                    .unwrap_or(&cycle[0])
+            } else {
+                cycle
+                    .iter()
+                    .rev()
+                    .find(|&&position| position < index)
+                    .unwrap_or(&cycle[cycle.len() - 1])
+            };
+            let (from, to) = (idents[index], idents[*target]);
+            quote! { #name::#from => #name::#to }
+        });
+        quote! { match self { #(#arms,)* } }
+    };
+    let (next, prev) = (step(true), step(false));
+    let positions = (0 .. idents.len()).map(|index| {
+        let ident = idents[index];
+        quote! { #name::#ident => #index }
+    });
+    Ok(quote! {
         impl #name {
-            pub fn num(&self) -> usize {
-                self.to_usize().unwrap()
+            /// The position of the variant in declaration order.
+            pub const fn num(&self) -> usize {
+                match self { #(#positions,)* }
             }
-            pub fn next(&self) -> #name {
-                return #name::from_usize(self.next_index()).unwrap();
+            pub const fn next(&self) -> #name {
+                #next
             }
-            pub fn prev(&self) -> #name {
-                return #name::from_usize(self.prev_index()).unwrap();
+            pub const fn prev(&self) -> #name {
+                #prev
             }
-            pub fn next_index(&self) -> usize {
-                return (self.num() + 1) % #loop_within;
+            pub const fn next_index(&self) -> usize {
+                self.next().num()
             }
-            pub fn prev_index(&self) -> usize {
-                return (self.num() + #loop_within - 1) % #loop_within;
+            pub const fn prev_index(&self) -> usize {
+                self.prev().num()
             }
         }
-    };
-    TokenStream::from(expanded)
+    })
+}
+#[proc_macro_derive(LoopableNumberedEnum, attributes(numbered_enum))]
+pub fn numbered_enum_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    numbered_enum(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 #[proc_macro_derive(RadioOption)]
 pub fn radio_option_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    if let Err(error) = enum_variants(&input, "RadioOption") {
+        return error.into_compile_error().into();
+    }
     let name = input.ident;
     let expanded = quote! {
         impl super::RadioOptionValue for #name {
-            fn selectable(&self) -> bool {true}
+            fn selectable(&self, _: &crate::AppContext) -> bool {true}
         }
     };
     TokenStream::from(expanded)
 }
+fn inner_state(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let has_common_state = match &input.data {
+        syn::Data::Struct(data) => data.fields.iter().any(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == "common_state")
+        }),
+        _ => false,
+    };
+    if !has_common_state {
+        return Err(Error::new_spanned(
+            name,
+            "InnerState needs a struct with a `common_state: InnerCommonState` field",
+        ));
+    }
+    Ok(quote! {
+        impl super::InnerState for #name {
+            fn state_mut(&mut self) -> &mut InnerCommonState {
+                &mut self.common_state
+            }
+        }
+    })
+}
 #[proc_macro_derive(InnerState)]
 pub fn inner_state_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-    let expanded = quote! {
-        impl super::InnerState for #name {
-            fn state(self) -> InnerCommonState {
-                self.common_state
+    inner_state(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+/// How a field of an inner is edited in its form.
+enum FieldKind {
+    Text,
+    Radio,
+    List,
+}
+struct FormField {
+    ident: Ident,
+    label: LitStr,
+    tip:   LitStr,
+    kind:  FieldKind,
+}
+/// `language_standard_version` to `LanguageStandardVersion`.
+fn upper_camel(ident: &Ident) -> Ident {
+    let name: String = ident
+        .to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    Ident::new(&name, ident.span())
+}
+fn form_field(field: &syn::Field, attr: &syn::Attribute) -> syn::Result<FormField> {
+    let (mut label, mut tip, mut kind) = (None, None, None);
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("label") {
+            label = Some(meta.value()?.parse::<LitStr>()?);
+        } else if meta.path.is_ident("tip") {
+            tip = Some(meta.value()?.parse::<LitStr>()?);
+        } else if meta.path.is_ident("kind") {
+            let value: Ident = meta.value()?.parse()?;
+            kind = Some(match value.to_string().as_str() {
+                "text" => FieldKind::Text,
+                "radio" => FieldKind::Radio,
+                "list" => FieldKind::List,
+                _ => {
+                    return Err(Error::new_spanned(
+                        value,
+                        "expected `text`, `radio` or `list`",
+                    ));
+                }
+            });
+        } else {
+            return Err(meta.error("expected `label`, `tip` or `kind`"));
+        }
+        Ok(())
+    })?;
+    let missing = |name: &str| Error::new_spanned(attr, format!("missing `{name} = ...`"));
+    Ok(FormField {
+        ident: field.ident.clone().unwrap(),
+        label: label.ok_or_else(|| missing("label"))?,
+        tip:   tip.ok_or_else(|| missing("tip"))?,
+        kind:  kind.ok_or_else(|| missing("kind"))?,
+    })
+}
+fn inner_form(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let syn::Data::Struct(syn::DataStruct {
+        fields: syn::Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        return Err(Error::new_spanned(
+            name,
+            "InnerForm can only be derived for structs with named fields",
+        ));
+    };
+    let mut form_fields = vec![];
+    for field in &fields.named {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("field"))
+        {
+            form_fields.push(form_field(field, attr)?);
+        }
+    }
+    if form_fields.is_empty() {
+        return Err(Error::new_spanned(
+            name,
+            "InnerForm needs at least one field marked with #[field(...)]",
+        ));
+    }
+    let field_enum = format_ident!("{}Field", name.to_string().trim_end_matches("Inner"));
+    let variants: Vec<Ident> = form_fields.iter().map(|f| upper_camel(&f.ident)).collect();
+    let labels = form_fields.iter().map(|f| &f.label);
+    let tips = form_fields.iter().map(|f| &f.tip);
+    let mut text_arms = vec![];
+    let mut field_arms = vec![];
+    let mut radio_arms = vec![];
+    for (f, variant) in form_fields.iter().zip(&variants) {
+        let ident = &f.ident;
+        // 类型不符时, 错误指向声明该字段的位置
+        let span = ident.span();
+        match f.kind {
+            FieldKind::Text => {
+                text_arms.push(quote_spanned! {span=>
+                    #field_enum::#variant => Some(&mut self.#ident),
+                });
+                field_arms.push(quote_spanned! {span=> #field_enum::#variant => &self.#ident, });
+            }
+            FieldKind::Radio => {
+                radio_arms.push(quote_spanned! {span=>
+                    #field_enum::#variant => Some(&mut self.#ident),
+                });
+                field_arms.push(quote_spanned! {span=>
+                    #field_enum::#variant => &self.#ident.value,
+                });
+            }
+            FieldKind::List => {
+                field_arms.push(quote_spanned! {span=>
+                    #field_enum::#variant => &self.#ident as &Vec<String>,
+                });
+            }
+        }
+    }
+    Ok(quote! {
+        #[derive(
+            Clone,
+            Copy,
+            ::strum_macros::Display,
+            ::strum_macros::EnumIter,
+            ::num_derive::FromPrimitive,
+            ::num_derive::ToPrimitive,
+        )]
+        enum #field_enum {
+            #(#variants),*
+        }
+        impl super::InnerFieldMapping<#field_enum> for #name {
+            #[allow(unreachable_patterns)]
+            fn get_focus_field_mut(&mut self, field: #field_enum) -> Option<&mut String> {
+                match field {
+                    #(#text_arms)*
+                    _ => None,
+                }
             }
 
-            fn with_state(&mut self, state: InnerCommonState) -> &mut Self {
-                self.common_state = state;
-                self
+            fn get_field(&self, field: #field_enum) -> &dyn ::std::fmt::Debug {
+                match field {
+                    #(#field_arms)*
+                }
+            }
+
+            #[allow(unreachable_patterns)]
+            fn get_radio(&mut self, field: #field_enum) -> Option<&mut dyn super::RadioOptionTrait> {
+                match field {
+                    #(#radio_arms)*
+                    _ => None,
+                }
             }
         }
-    };
-    TokenStream::from(expanded)
+        impl super::InnerTipLabel for #name {
+            fn tips() -> &'static [&'static str] {
+                &[#(#tips),*]
+            }
+
+            fn labels() -> &'static [&'static str] {
+                &[#(#labels),*]
+            }
+        }
+    })
 }
-/// # Panics
-#[proc_macro_derive(EnumFunc, attributes(enum_func))]
-pub fn enum_func_derive(input: TokenStream) -> TokenStream {
+/// Generates the field enum of an inner, named after it, and its
+/// `InnerFieldMapping` and `InnerTipLabel` from `#[field(...)]` attributes.
+#[proc_macro_derive(InnerForm, attributes(field))]
+pub fn inner_form_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    inner_form(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+/// `name: Type` or `name: Type = default`, declaring a function of `EnumFunc`.
+struct FuncDecl {
+    name:    Ident,
+    ty:      syn::Type,
+    default: Option<syn::Expr>,
+}
+impl syn::parse::Parse for FuncDecl {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        let default = if input.parse::<Option<Token![=]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { name, ty, default })
+    }
+}
+/// `T` of `Option<T>`.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(inner)) if segment.ident == "Option" => Some(inner),
+        _ => None,
+    }
+}
+/// The return type of a function nobody declared, told by the values of
+/// every variant: strings, several strings in one variant making a slice, a
+/// `bool` or an integer with a suffix.
+fn infer_func_type(name: &Ident, values: &[&[syn::Expr]]) -> syn::Result<syn::Type> {
+    let literals: Option<Vec<Vec<&syn::Lit>>> = values
+        .iter()
+        .map(|values| {
+            values
+                .iter()
+                .map(|value| match value {
+                    syn::Expr::Lit(lit) => Some(&lit.lit),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+    let literals = literals.unwrap_or_default();
+    let all = |matches: fn(&syn::Lit) -> bool| {
+        !literals.is_empty() && literals.iter().flatten().all(|lit| matches(lit))
+    };
+    let single = literals.iter().all(|values| values.len() == 1);
+    let suffixes: Vec<&str> = literals
+        .iter()
+        .flatten()
+        .filter_map(|lit| match lit {
+            syn::Lit::Int(int) => Some(int.suffix()),
+            _ => None,
+        })
+        .collect();
+    let ty = if all(|lit| matches!(lit, syn::Lit::Str(_))) {
+        if single {
+            quote! { &'static str }
+        } else {
+            quote! { &'static [&'static str] }
+        }
+    } else if single && all(|lit| matches!(lit, syn::Lit::Bool(_))) {
+        quote! { bool }
+    } else if single
+        && all(|lit| matches!(lit, syn::Lit::Int(_)))
+        && !suffixes[0].is_empty()
+        && suffixes.iter().all(|suffix| *suffix == suffixes[0])
+    {
+        let suffix = Ident::new(suffixes[0], name.span());
+        quote! { #suffix }
+    } else {
+        return Err(Error::new_spanned(
+            values
+                .iter()
+                .find_map(|values| values.first())
+                .map_or_else(|| name.to_token_stream(), ToTokens::to_token_stream),
+            format!(
+                "cannot infer the return type of `{name}`, declare it with \
+                     #[enum_func({name}: Type)]"
+            ),
+        ));
+    };
+    syn::parse2(ty)
+}
+/// `values` as a `ty`: several of them make a slice, a string becomes a
+/// `Path` when one is expected and `Option`s get `Some`.
+fn func_value(
+    ty: &syn::Type,
+    values: &[syn::Expr],
+    span: proc_macro2::Span,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let inner = option_inner(ty).unwrap_or(ty);
+    let value = match (inner, values) {
+        (syn::Type::Reference(reference), _) if matches!(*reference.elem, syn::Type::Slice(_)) => {
+            quote_spanned! {span=> &[#(#values),*] }
+        }
+        (syn::Type::Reference(reference), [value])
+            if matches!(&*reference.elem, syn::Type::Path(path)
+                if path.path.segments.last().is_some_and(|segment| segment.ident == "Path")) =>
+        {
+            quote_spanned! {span=> ::std::path::Path::new(#value) }
+        }
+        (_, [value]) => quote_spanned! {span=> #value },
+        _ => return Err(Error::new(span, "expected a single value")),
+    };
+    Ok(if option_inner(ty).is_some() {
+        quote_spanned! {span=> ::std::option::Option::Some(#value) }
+    } else {
+        value
+    })
+}
+fn enum_func(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let enum_name = &input.ident;
-    let mut func_branches: HashMap<String, Vec<proc_macro2::TokenStream>> = HashMap::new();
-    if let syn::Data::Enum(data_enum) = &input.data {
-        for v in &data_enum.variants {
-            let variant_name = &v.ident;
-            for attr in &v.attrs {
-                if !attr.path().is_ident("enum_func") {
-                    continue;
-                }
-                if let Ok(nested) = attr.parse_args_with(
-                    syn::punctuated::Punctuated::<Meta, Token![,]>::parse_terminated,
-                ) {
-                    for meta in nested {
-                        if let Meta::List(ml) = meta {
-                            let mut func_name: String = String::new();
-                            if let Some(func_name_ident) = ml.path.get_ident() {
-                                func_name = func_name_ident.to_string();
-                            }
-                            if !func_name.is_empty()
-                                && let Ok(s) = ml.parse_args::<LitStr>()
-                            {
-                                let func_value = s.value();
-                                func_branches.entry(func_name).or_default().push(quote! {
-                                    #enum_name::#variant_name => #func_value.to_string()
-                                });
-                            }
-                        }
-                    }
+    let variants = enum_variants(input, "EnumFunc")?;
+    let mut decls: Vec<FuncDecl> = vec![];
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("enum_func"))
+    {
+        decls.extend(attr.parse_args_with(
+            syn::punctuated::Punctuated::<FuncDecl, Token![,]>::parse_terminated,
+        )?);
+    }
+    // 每个变体的每个函数的值, 按出现顺序保存, 保持函数的生成顺序稳定
+    let mut values: Vec<Vec<(Ident, Vec<syn::Expr>, proc_macro2::Span)>> = vec![];
+    for v in variants {
+        let mut variant_values: Vec<(Ident, Vec<syn::Expr>, proc_macro2::Span)> = vec![];
+        for attr in v
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("enum_func"))
+        {
+            let nested = attr.parse_args_with(
+                syn::punctuated::Punctuated::<Meta, Token![,]>::parse_terminated,
+            )?;
+            for meta in nested {
+                let Meta::List(ml) = &meta else {
+                    return Err(Error::new_spanned(meta, "expected `name(value, ...)`"));
+                };
+                let Some(func_name) = ml.path.get_ident() else {
+                    return Err(Error::new_spanned(&ml.path, "expected a function name"));
+                };
+                if variant_values.iter().any(|(name, ..)| name == func_name) {
+                    return Err(Error::new_spanned(
+                        func_name,
+                        format!("duplicate value of `{func_name}`"),
+                    ));
                 }
+                let args = ml.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Expr, Token![,]>::parse_terminated,
+                )?;
+                variant_values.push((
+                    func_name.clone(),
+                    args.into_iter().collect(),
+                    syn::spanned::Spanned::span(&ml.tokens),
+                ));
             }
         }
+        values.push(variant_values);
+    }
+    for (func_name, ..) in values.iter().flatten() {
+        if !decls.iter().any(|decl| decl.name == *func_name) {
+            let func_values: Vec<&[syn::Expr]> = values
+                .iter()
+                .flatten()
+                .filter(|(name, ..)| name == func_name)
+                .map(|(_, func_values, _)| func_values.as_slice())
+                .collect();
+            decls.push(FuncDecl {
+                name:    func_name.clone(),
+                ty:      infer_func_type(func_name, &func_values)?,
+                default: None,
+            });
+        }
     }
-    let generated_functions = func_branches.iter().map(|(func_name, branches)| {
-        let func_name = parse_str::<proc_macro2::TokenStream>(func_name).unwrap();
-        quote! {
-            fn #func_name(self) -> String {
+    let mut generated_functions = vec![];
+    for decl in &decls {
+        let mut branches = vec![];
+        for (v, variant_values) in variants.iter().zip(&values) {
+            let variant_name = &v.ident;
+            let value = match variant_values.iter().find(|(name, ..)| *name == decl.name) {
+                Some((_, func_values, span)) => func_value(&decl.ty, func_values, *span)?,
+                None => match (&decl.default, option_inner(&decl.ty)) {
+                    // `= None` 是 `Option` 本身的值, 不是 `Some` 里的
+                    (Some(syn::Expr::Path(path)), Some(_)) if path.path.is_ident("None") => {
+                        quote! { ::std::option::Option::None }
+                    }
+                    (Some(default), _) => func_value(
+                        &decl.ty,
+                        std::slice::from_ref(default),
+                        syn::spanned::Spanned::span(default),
+                    )?,
+                    (None, Some(_)) => quote! { ::std::option::Option::None },
+                    (None, None) => {
+                        return Err(Error::new_spanned(
+                            v,
+                            format!(
+                                "variant `{variant_name}` is missing #[enum_func({}(...))]",
+                                decl.name
+                            ),
+                        ));
+                    }
+                },
+            };
+            branches.push(quote! { #enum_name::#variant_name => #value });
+        }
+        let (func_name, ty) = (&decl.name, &decl.ty);
+        generated_functions.push(quote! {
+            pub(crate) fn #func_name(self) -> #ty {
                 match self {
                     #(#branches),*
                 }
             }
-        }
-    });
-    let expanded = quote! {
+        });
+    }
+    Ok(quote! {
         impl #enum_name {
             #(#generated_functions)*
         }
-    };
-    TokenStream::from(expanded)
+    })
 }
-/// # Panics
-#[proc_macro_derive(ExecutableEnum, attributes(exe))]
-pub fn executable_enum_derive(input: TokenStream) -> TokenStream {
+#[proc_macro_derive(EnumFunc, attributes(enum_func))]
+pub fn enum_func_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    enum_func(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+fn executable_enum(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &input.ident;
-    let syn::Data::Enum(data) = &input.data else {
-        panic!("ExecutableEnum can only be derived for enums")
-    };
-    let match_arms: Vec<_> = data
-        .variants
-        .iter()
-        .map(|variant| {
-            let variant_name = &variant.ident;
-            // 查找 #[exe("...")] 属性
-            let mut exe_value = None;
-            for attr in &variant.attrs {
-                if attr.path().is_ident("exe") {
-                    let value = attr
-                        .parse_args::<syn::LitStr>()
-                        .expect("Expected string literal in #[exe] attribute");
-                    exe_value = Some(value.value());
-                    break;
-                }
-            }
-            let Some(exe_str) = exe_value else {
-                panic!("Variant {variant_name} is missing #[exe] attribute")
-            };
-            quote! {
-                #name::#variant_name => #exe_str,
-            }
-        })
-        .collect();
-    let expanded = quote! {
+    let mut match_arms = vec![];
+    for variant in enum_variants(input, "ExecutableEnum")? {
+        let variant_name = &variant.ident;
+        // 查找 #[exe("...")] 属性
+        let Some(attr) = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("exe"))
+        else {
+            return Err(Error::new_spanned(
+                variant,
+                format!("variant `{variant_name}` is missing #[exe(\"...\")]"),
+            ));
+        };
+        let exe_str = attr.parse_args::<LitStr>()?.value();
+        match_arms.push(quote! {
+            #name::#variant_name => #exe_str,
+        });
+    }
+    Ok(quote! {
         impl crate::common::ExecutableEnumTrait for #name {
             fn exe(&self) -> String {
                 match self {
@@ -162,6 +601,12 @@ pub fn executable_enum_derive(input: TokenStream) -> TokenStream {
                 }.to_string()
             }
         }
-    };
-    TokenStream::from(expanded)
+    })
+}
+#[proc_macro_derive(ExecutableEnum, attributes(exe))]
+pub fn executable_enum_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    executable_enum(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
@@ -0,0 +1,22 @@
+use project_setup_derive::EnumFunc;
+use std::path::Path;
+#[derive(Clone, Copy, EnumFunc)]
+#[enum_func(main_file: &'static Path, compiled: bool = true, extension: Option<&'static str>)]
+enum Language {
+    #[enum_func(main_file("Main.java"), run("java", "Main.java"), year(1995u16))]
+    #[enum_func(extension("java"))]
+    Java,
+    #[enum_func(main_file("main.py"), run("python3"), year(1991u16), compiled(false))]
+    Python,
+}
+#[test]
+fn test_enum_func_typed_returns() {
+    assert_eq!(Language::Java.main_file(), Path::new("Main.java"));
+    assert_eq!(Language::Java.run(), ["java", "Main.java"]);
+    assert_eq!(Language::Python.run(), ["python3"]);
+    assert_eq!(Language::Python.year(), 1991);
+    assert!(Language::Java.compiled());
+    assert!(!Language::Python.compiled());
+    assert_eq!(Language::Java.extension(), Some("java"));
+    assert_eq!(Language::Python.extension(), None);
+}
@@ -0,0 +1,30 @@
+use project_setup_derive::EnumFunc;
+use std::path::Path;
+#[derive(Clone, Copy, EnumFunc)]
+#[enum_func(
+    extension: Option<&'static str> = "txt",
+    license: Option<&'static str> = None,
+    config: &'static Path = "config.toml",
+    name: &'static str = "plain"
+)]
+enum Format {
+    #[enum_func(extension("md"), license("MIT"), config("book.toml"), aliases("md"))]
+    Markdown,
+    // 类型由所有变体的值推断, 不只看第一个
+    #[enum_func(name("text"), aliases("txt", "text"))]
+    Text,
+    #[enum_func(aliases("raw"))]
+    Raw,
+}
+fn main() {
+    assert_eq!(Format::Markdown.extension(), Some("md"));
+    assert_eq!(Format::Text.extension(), Some("txt"));
+    assert_eq!(Format::Markdown.license(), Some("MIT"));
+    assert_eq!(Format::Raw.license(), None);
+    assert_eq!(Format::Raw.config(), Path::new("config.toml"));
+    assert_eq!(Format::Markdown.config(), Path::new("book.toml"));
+    assert_eq!(Format::Markdown.name(), "plain");
+    assert_eq!(Format::Text.name(), "text");
+    assert_eq!(Format::Markdown.aliases(), ["md"]);
+    assert_eq!(Format::Text.aliases(), ["txt", "text"]);
+}
@@ -0,0 +1,9 @@
+use project_setup_derive::EnumFunc;
+#[derive(EnumFunc)]
+#[enum_func(compiled: bool = false)]
+enum Language {
+    #[enum_func(compiled("yes"))]
+    Java,
+    Python,
+}
+fn main() {}
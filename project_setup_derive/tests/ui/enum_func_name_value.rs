@@ -0,0 +1,7 @@
+use project_setup_derive::EnumFunc;
+#[derive(EnumFunc)]
+enum Language {
+    #[enum_func(extension = "java")]
+    Java,
+}
+fn main() {}
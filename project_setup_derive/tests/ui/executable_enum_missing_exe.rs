@@ -0,0 +1,8 @@
+use project_setup_derive::ExecutableEnum;
+#[derive(ExecutableEnum)]
+enum Tool {
+    #[exe("git")]
+    Git,
+    Svn,
+}
+fn main() {}
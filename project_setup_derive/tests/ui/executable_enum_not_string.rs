@@ -0,0 +1,7 @@
+use project_setup_derive::ExecutableEnum;
+#[derive(ExecutableEnum)]
+enum Tool {
+    #[exe(git)]
+    Git,
+}
+fn main() {}
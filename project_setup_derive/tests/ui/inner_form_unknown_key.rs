@@ -0,0 +1,7 @@
+use project_setup_derive::InnerForm;
+#[derive(InnerForm)]
+struct CargoInner {
+    #[field(label = "name", hint = "Please input the name", kind = text)]
+    name: String,
+}
+fn main() {}
@@ -0,0 +1,7 @@
+use project_setup_derive::InnerForm;
+#[derive(InnerForm)]
+struct CargoInner {
+    #[field(label = "name", tip = "Please input the name", kind = checkbox)]
+    name: String,
+}
+fn main() {}
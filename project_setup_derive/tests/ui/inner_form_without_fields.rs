@@ -0,0 +1,6 @@
+use project_setup_derive::InnerForm;
+#[derive(InnerForm)]
+struct CargoInner {
+    name: String,
+}
+fn main() {}
@@ -0,0 +1,6 @@
+use project_setup_derive::InnerState;
+#[derive(InnerState)]
+struct CargoInner {
+    name: String,
+}
+fn main() {}
@@ -0,0 +1,9 @@
+use project_setup_derive::LoopableNumberedEnum;
+#[derive(LoopableNumberedEnum)]
+enum Side {
+    #[numbered_enum(skip)]
+    Left,
+    #[numbered_enum(skip)]
+    Right,
+}
+fn main() {}
@@ -0,0 +1,7 @@
+use project_setup_derive::LoopableNumberedEnum;
+#[derive(LoopableNumberedEnum)]
+enum Side {
+    Left,
+    Right(u8),
+}
+fn main() {}
@@ -0,0 +1,8 @@
+use project_setup_derive::LoopableNumberedEnum;
+#[derive(LoopableNumberedEnum)]
+#[numbered_enum(loop_within = 2)]
+enum Side {
+    Left,
+    Right,
+}
+fn main() {}
@@ -0,0 +1,4 @@
+use project_setup_derive::LoopableNumberedEnum;
+#[derive(LoopableNumberedEnum)]
+struct Side;
+fn main() {}
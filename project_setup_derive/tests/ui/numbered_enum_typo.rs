@@ -0,0 +1,8 @@
+use project_setup_derive::LoopableNumberedEnum;
+#[derive(LoopableNumberedEnum)]
+enum Side {
+    Left,
+    #[numbered_enum(skp)]
+    Right,
+}
+fn main() {}
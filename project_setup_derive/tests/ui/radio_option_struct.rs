@@ -0,0 +1,4 @@
+use project_setup_derive::RadioOption;
+#[derive(RadioOption)]
+struct Side;
+fn main() {}
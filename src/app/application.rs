@@ -1,51 +1,357 @@
-use super::{CargoInner, CmakeInner, SpringBootInner, WipInner, inner::Inner};
-use crate::{Args, common::ProjectType};
-use anyhow::Result;
+use super::{
+    CargoInner, CmakeInner, Confirmation, ConfirmationOutput, Creation, CreationOutput,
+    PrepareProgress, Preview, Recent, RecentOutput, SpringBootInner, TemplateInner, WipInner,
+    inner::Inner,
+};
+use crate::{
+    AppContext,
+    common::{Editor, ProjectType, writable_directory},
+    generate::{Report, generate_with},
+    history::{History, Record},
+};
+use anyhow::{Context, Result, bail};
 use ratatui::{
     Frame, Terminal,
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    },
     layout::Layout,
     prelude::*,
     style::{Color, Modifier, Style},
     widgets::{Block, Borders, List, ListState, Paragraph},
 };
 use ratatui_macros::constraints;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 use strum::IntoEnumIterator;
+/// A project created during this session.
+#[derive(Clone)]
+struct Created {
+    project_path: PathBuf,
+    main_file:    String,
+    editor:       Editor,
+    /// Its editor is opened when quitting, unless it was opened already.
+    open_on_quit: bool,
+}
+impl Created {
+    /// The height of the panel listing `count` projects, at most a third of
+    /// the `height` of the left side.
+    fn height(count: usize, height: u16) -> u16 {
+        if count == 0 {
+            return 0;
+        }
+        u16::try_from(count + 2).unwrap_or(u16::MAX).min(height / 3)
+    }
+
+    /// Lists the projects, scrolled to the newest one when they do not fit.
+    fn render(created: &[Self], frame: &mut Frame, area: Rect) {
+        frame.render_stateful_widget(
+            List::new(created.iter().map(|created| {
+                format!(
+                    "{}{}",
+                    created
+                        .project_path
+                        .file_name()
+                        .unwrap_or_default()
+                        .display(),
+                    if created.open_on_quit { " ✎" } else { "" }
+                )
+            }))
+            .block(Block::default().title("Created").borders(Borders::ALL)),
+            area,
+            &mut ListState::default().with_selected(created.len().checked_sub(1)),
+        );
+    }
+}
 pub struct Application {
+    context:         Arc<AppContext>,
     selected:        ProjectType,
     focus_left_side: bool,
-    inners:          Vec<Box<dyn Inner>>,
+    /// Created once the preparation of their project type is done.
+    inners:          Vec<Option<Box<dyn Inner>>>,
+    preparations:    Vec<Option<PrepareProgress>>,
     default_inner:   Option<ProjectType>,
+    /// Shown instead of creating the project when running with `--dry-run`.
+    preview:         Option<Preview>,
+    /// Asked before creating the project, so that a stray Enter creates
+    /// nothing.
+    confirmation:    Option<Confirmation>,
+    /// The project being created, then what to do once it is.
+    creation:        Option<Creation>,
+    created:         Vec<Created>,
+    /// The projects created before, opened with Ctrl-R.
+    recent:          Option<Recent>,
+    /// A record picked in `recent`, restored once its project type is
+    /// prepared, then confirmed if it is re-generated.
+    restoring:       Option<(Record, bool)>,
 }
 impl Application {
+    /// Starts preparing every project type which can be selected in the
+    /// background.
     #[must_use]
-    pub fn new(args: Args) -> Self {
-        let inners: Vec<Box<dyn Inner>> = vec![
-            Box::new(SpringBootInner::new()),
-            Box::new(CmakeInner::new()),
-            Box::new(WipInner {}),
-            Box::new(CargoInner::new()),
-        ];
+    pub fn new(context: Arc<AppContext>) -> Self {
+        let args = context.args;
         Self {
             selected: ProjectType::default(),
             focus_left_side: args.project_type.is_none(),
-            inners,
+            inners: ProjectType::iter().map(|_| None).collect(),
+            preparations: ProjectType::iter()
+                .map(|project_type| {
+                    args.project_type
+                        .is_none_or(|p| p == project_type)
+                        .then(|| PrepareProgress::project_type(context.clone(), project_type))
+                })
+                .collect(),
             default_inner: args.project_type,
+            preview: None,
+            confirmation: None,
+            creation: None,
+            created: vec![],
+            recent: None,
+            restoring: None,
+            context,
+        }
+    }
+
+    fn new_inner(context: &Arc<AppContext>, project_type: ProjectType) -> Box<dyn Inner> {
+        match project_type {
+            ProjectType::SpringBoot => Box::new(SpringBootInner::new(context)),
+            ProjectType::Cmake => Box::new(CmakeInner::new(context)),
+            ProjectType::Maven => Box::new(WipInner {}),
+            ProjectType::Cargo => Box::new(CargoInner::new(context)),
+            ProjectType::Template => Box::new(TemplateInner::new(context)),
+            ProjectType::Import => Box::new(TemplateInner::import(context)),
+        }
+    }
+
+    fn update_preparations(&mut self) {
+        for project_type in ProjectType::iter() {
+            let index = project_type.num();
+            if let Some(preparation) = self.preparations[index].as_mut() {
+                preparation.update();
+                if preparation.prepared() && self.inners[index].is_none() {
+                    self.inners[index] = Some(Self::new_inner(&self.context, project_type));
+                }
+            }
+        }
+        self.restore();
+    }
+
+    /// Fills a new form with the answers of the record picked in `recent`.
+    fn restore(&mut self) {
+        let Some((record, _)) = &self.restoring else {
+            return;
+        };
+        let project_type = record.project_type;
+        if self.default_inner.is_none() {
+            self.selected = project_type;
+        }
+        let index = project_type.num();
+        if self.inners[index].is_none() {
+            return;
+        }
+        let Some((record, regenerate)) = self.restoring.take() else {
+            return;
+        };
+        let mut inner = Self::new_inner(&self.context, project_type);
+        inner.restore(&record.values);
+        // 导入的模板还在加载时无法立即生成, 只填好表单
+        let confirm = regenerate && inner.generation().is_some();
+        self.inners[index] = Some(inner);
+        self.focus_left_side = false;
+        if confirm {
+            self.confirm();
+        }
+    }
+
+    /// Asks before creating the project of the current form, or previews it
+    /// with `--dry-run`.
+    fn confirm(&mut self) {
+        let index = self.current().num();
+        let Some(inner) = self.inners[index].as_mut() else {
+            return;
+        };
+        let report = Self::dry_run(&self.context, inner.as_ref());
+        if self.context.args.dry_run {
+            self.preview = Some(Preview::new(report));
+        } else {
+            self.confirmation = Some(Confirmation::new(inner.summary(), report));
+        }
+    }
+
+    /// Plans what creating the project of `inner` would do, without doing it.
+    fn dry_run(context: &AppContext, inner: &dyn Inner) -> Result<Report> {
+        let (options, target) = inner
+            .generation()
+            .context("This project type can not be created yet")?;
+        // 只在确认创建前试写一次, 而不是每次编辑路径时
+        if !context.args.dry_run {
+            writable_directory(&target)?;
+        }
+        generate_with(context, options, &target, true)
+    }
+
+    /// Opens the created project, leaving the terminal to editors running in
+    /// it until they exit.
+    fn open_editor<B: Backend>(&self, terminal: &mut Terminal<B>, created: &Created) -> Result<()>
+    where
+        <B as Backend>::Error: Send,
+        <B as Backend>::Error: Sync,
+        <B as Backend>::Error: 'static,
+    {
+        let editor = created.editor;
+        let (project_path, main_file) = (created.project_path.clone(), created.main_file.clone());
+        if editor.detached() {
+            editor.run(self.context.executables(), project_path, main_file)?;
+            return Ok(());
+        }
+        disable_raw_mode()?;
+        execute!(std::io::stdout(), LeaveAlternateScreen)?;
+        let status = editor.run(self.context.executables(), project_path, main_file);
+        enable_raw_mode()?;
+        execute!(std::io::stdout(), EnterAlternateScreen)?;
+        terminal.clear()?;
+        status?;
+        Ok(())
+    }
+
+    /// Opens the editors deferred until quitting, every one of them even if
+    /// some cannot be opened.
+    fn quit<B: Backend>(&self, terminal: &mut Terminal<B>) -> Result<()>
+    where
+        <B as Backend>::Error: Send,
+        <B as Backend>::Error: Sync,
+        <B as Backend>::Error: 'static,
+    {
+        let errors: Vec<String> = self
+            .created
+            .iter()
+            .filter(|created| created.open_on_quit)
+            .filter_map(|created| {
+                self.open_editor(terminal, created).err().map(|error| {
+                    format!(
+                        "cannot open {} with {}: {error:#}",
+                        created.project_path.display(),
+                        created.editor
+                    )
+                })
+            })
+            .collect();
+        if !errors.is_empty() {
+            bail!("{}", errors.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Appends the project just created to the history with the answers of
+    /// its form, the creation tells when it cannot be.
+    fn record_history(&mut self) {
+        let project_type = self.current();
+        let (Some(creation), Some(inner)) = (
+            self.creation.as_mut(),
+            self.inners[project_type.num()].as_mut(),
+        ) else {
+            return;
+        };
+        let Some(report) = creation.report() else {
+            return;
+        };
+        let record = Record::new(
+            &self.context,
+            project_type,
+            inner.summary(),
+            report.project_path.clone(),
+        );
+        // 历史记录写入失败不影响已创建的项目, 只提示
+        if let Err(error) = History::append(&self.context, &record) {
+            creation.warn(format!("Not added to the history: {error:#}"));
+        }
+    }
+
+    /// Keeps the project created by `creation` for the rest of the session.
+    fn record(&mut self, creation: &Creation) {
+        if let Some(report) = creation.report() {
+            self.created.push(Created {
+                project_path: report.project_path.clone(),
+                main_file:    report.main_file.clone(),
+                editor:       creation.editor(),
+                open_on_quit: creation.editor() != Editor::NotNeed,
+            });
+        }
+    }
+
+    fn current(&self) -> ProjectType {
+        self.default_inner.unwrap_or(self.selected)
+    }
+
+    /// Renders the inner of the current project type, or the progress of its
+    /// preparation while it is not ready yet.
+    fn render_inner(&mut self, frame: &mut Frame, focus_right_side: bool, area: Rect) {
+        let index = self.current().num();
+        if let Some(creation) = &self.creation {
+            creation.render(frame, area);
+        } else if let Some(inner) = self.inners[index].as_mut() {
+            inner.render(frame, focus_right_side, area);
+            if let Some(preview) = &self.preview {
+                preview.render(frame, area);
+            }
+            if let Some(confirmation) = &self.confirmation {
+                confirmation.render(frame, area);
+            }
+        } else if let Some(preparation) = &self.preparations[index] {
+            let loading_area = Layout::vertical(constraints![*=1,==3,*=1]).split(area)[1];
+            preparation.render(frame, loading_area);
+        }
+        if let Some(recent) = &self.recent {
+            recent.render(frame, area);
+        }
+    }
+
+    fn bottom_help_message(&self) -> String {
+        self.inners[self.current().num()]
+            .as_ref()
+            .map(|inner| inner.bottom_help_message())
+            .unwrap_or_default()
+    }
+
+    fn help_message(&self) -> String {
+        let quit = if self.created.iter().any(|created| created.open_on_quit) {
+            "q: quit and open editors"
+        } else {
+            "q: quit"
+        };
+        if self.recent.is_some() {
+            "j/k: move | Enter: reuse as form | g: re-generate | Esc: back".to_string()
+        } else if self.focus_left_side {
+            format!("j/k: move | Enter: choose | Ctrl-R: recent | {quit}")
+        } else if let Some(creation) = &self.creation {
+            if creation.done() {
+                "←/→: choose | j/k: scroll | Enter: press".to_string()
+            } else {
+                "j/k: scroll".to_string()
+            }
+        } else if self.preview.is_some() {
+            format!("j/k: scroll | Esc: back to the form | {quit}")
+        } else if self.confirmation.is_some() {
+            "←/→: choose | j/k: scroll | Enter: press | Esc: back to the form".to_string()
+        } else if self.default_inner.is_some() {
+            format!("{}Ctrl-R: recent | {quit}", self.bottom_help_message())
+        } else {
+            format!(
+                "{}Ctrl-R: recent | Esc: focus back to left",
+                self.bottom_help_message()
+            )
         }
     }
 
     fn ui(&mut self, frame: &mut Frame) {
-        if let Some(default_inner) = self.default_inner {
-            let inner = self.inners[default_inner.num()].as_mut();
-            inner.render(frame, true, frame.area());
+        if self.default_inner.is_some() {
+            self.render_inner(frame, true, frame.area());
             // 底部帮助栏
-            let help_bar = Paragraph::new(if self.focus_left_side {
-                "j/k: move | Enter: choose | q: quit".to_string()
-            } else {
-                format!("{}q: quit", inner.bottom_help_message())
-            })
-            .style(Style::default().fg(Color::Gray))
-            .centered();
+            let help_bar = Paragraph::new(self.help_message())
+                .style(Style::default().fg(Color::Gray))
+                .centered();
             let bottom_layout = Layout::vertical(constraints![>=0,==1]).split(frame.area());
             frame.render_widget(help_bar, bottom_layout[1]);
         } else {
@@ -70,11 +376,17 @@ impl Application {
                         .add_modifier(Modifier::BOLD),
                 )
                 .highlight_symbol("» ");
+            let left_layout = Layout::vertical(constraints![
+                >=0,
+                ==Created::height(self.created.len(), main_layout[0].height)
+            ])
+            .split(main_layout[0]);
             frame.render_stateful_widget(
                 list,
-                main_layout[0],
+                left_layout[0],
                 &mut ListState::default().with_selected(Some(self.selected.num())),
             );
+            Created::render(&self.created, frame, left_layout[1]);
             let right_block = Block::default()
                 .title(self.selected.to_string())
                 .borders(Borders::ALL)
@@ -84,20 +396,15 @@ impl Application {
                     Color::Red
                 }));
             frame.render_widget(&right_block, main_layout[1]);
-            let inner = self.inners[self.selected.num()].as_mut();
-            inner.render(
+            self.render_inner(
                 frame,
                 !self.focus_left_side,
                 right_block.inner(main_layout[1]),
             );
             // 底部帮助栏
-            let help_bar = Paragraph::new(if self.focus_left_side {
-                "j/k: move | Enter: choose | q: quit".to_string()
-            } else {
-                format!("{}Esc: focus back to left", inner.bottom_help_message())
-            })
-            .style(Style::default().fg(Color::Gray))
-            .centered();
+            let help_bar = Paragraph::new(self.help_message())
+                .style(Style::default().fg(Color::Gray))
+                .centered();
             let bottom_layout = Layout::vertical(constraints![>=0,==1]).split(frame.area());
             frame.render_widget(help_bar, bottom_layout[1]);
         }
@@ -112,15 +419,57 @@ impl Application {
         <B as Backend>::Error: 'static,
     {
         loop {
+            self.update_preparations();
+            if let Some(creation) = self.creation.as_mut()
+                && creation.update()
+            {
+                self.record_history();
+            }
             terminal.draw(|f| self.ui(f))?;
-            // 处理输入事件
-            if let Event::Key(key) = event::read()? {
+            // 处理输入事件, 没有输入时也要定时刷新准备进度
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            let event = event::read()?;
+            if let Event::Paste(text) = &event {
+                let overlaid = self.creation.is_some()
+                    || self.preview.is_some()
+                    || self.confirmation.is_some()
+                    || self.recent.is_some();
+                let current = self.current().num();
+                if !self.focus_left_side
+                    && !overlaid
+                    && let Some(inner) = self.inners[current].as_mut()
+                {
+                    inner.handle_paste(text);
+                }
+                continue;
+            }
+            if let Event::Key(key) = event {
                 if key.kind != KeyEventKind::Press {
                     continue;
                 }
-                if self.focus_left_side {
+                let overlaid = self.creation.is_some()
+                    || self.preview.is_some()
+                    || self.confirmation.is_some();
+                if let Some(recent) = self.recent.as_mut() {
+                    match recent.handle_keyevent(key) {
+                        Some(RecentOutput::Reuse(record)) => self.restoring = Some((record, false)),
+                        Some(RecentOutput::Regenerate(record)) => {
+                            self.restoring = Some((record, true));
+                        }
+                        Some(RecentOutput::Back) => {}
+                        None => continue,
+                    }
+                    self.recent = None;
+                } else if !overlaid
+                    && key.code == KeyCode::Char('r')
+                    && key.modifiers.contains(KeyModifiers::CONTROL)
+                {
+                    self.recent = Some(Recent::new(&self.context, self.default_inner));
+                } else if self.focus_left_side {
                     match key.code {
-                        KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Char('q') => return self.quit(terminal),
                         KeyCode::Char('j') if self.default_inner.is_none() => {
                             self.selected = self.selected.next();
                         }
@@ -130,28 +479,125 @@ impl Application {
                         KeyCode::Enter => self.focus_left_side = false,
                         _ => {}
                     }
-                } else {
-                    let inner = self.inners[if let Some(default_inner) = self.default_inner {
-                        default_inner
-                    } else {
-                        self.selected
+                } else if let Some(creation) = self.creation.as_mut() {
+                    let Some(output) = creation.handle_keyevent(key) else {
+                        continue;
+                    };
+                    if let Some(creation) = self.creation.take() {
+                        self.record(&creation);
+                    }
+                    match output {
+                        CreationOutput::OpenEditor => {
+                            if let Some(created) = self.created.last_mut() {
+                                created.open_on_quit = false;
+                                let created = created.clone();
+                                self.open_editor(terminal, &created)?;
+                            }
+                            // 保留表单内容, 作为下一个项目的起点
+                            self.focus_left_side = self.default_inner.is_none();
+                        }
+                        CreationOutput::CreateAnother => {
+                            self.focus_left_side = self.default_inner.is_none();
+                        }
+                        CreationOutput::BackToForm => {}
+                        CreationOutput::Quit => return self.quit(terminal),
                     }
-                    .num()]
-                    .as_mut();
+                } else if let Some(preview) = self.preview.as_mut() {
+                    match key.code {
+                        KeyCode::Char('q') => return self.quit(terminal),
+                        KeyCode::Char('j') | KeyCode::Down => preview.scroll_down(),
+                        KeyCode::Char('k') | KeyCode::Up => preview.scroll_up(),
+                        KeyCode::Esc => self.preview = None,
+                        _ => {}
+                    }
+                } else if let Some(confirmation) = self.confirmation.as_mut() {
+                    match confirmation.handle_keyevent(key) {
+                        Some(ConfirmationOutput::Confirm) => {
+                            self.confirmation = None;
+                            if let Some(inner) = self.inners[self.current().num()].as_ref()
+                                && let Some((options, target)) = inner.generation()
+                            {
+                                self.creation = Some(Creation::spawn(
+                                    self.context.clone(),
+                                    options,
+                                    target,
+                                    inner.editor(),
+                                ));
+                            }
+                        }
+                        Some(ConfirmationOutput::Back) => self.confirmation = None,
+                        None => {}
+                    }
+                } else {
+                    let current = self.current().num();
+                    let Some(inner) = self.inners[current].as_mut() else {
+                        // 准备中只能返回或退出
+                        match key.code {
+                            KeyCode::Esc if self.default_inner.is_none() => {
+                                self.focus_left_side = true;
+                            }
+                            KeyCode::Char('q') if self.default_inner.is_some() => {
+                                return self.quit(terminal);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    };
                     let res = inner.handle_keyevent(key);
                     if !res.esc_handled && self.default_inner.is_none() && key.code == KeyCode::Esc
                     {
                         self.focus_left_side = true;
                     }
                     if res.exit {
-                        inner.create_and_edit()?;
-                        return Ok(());
+                        self.confirm();
+                        continue;
                     }
-                    if self.default_inner.is_some() && key.code == KeyCode::Char('q') {
-                        return Ok(());
+                    // 在文本框里输入的 q 不退出
+                    if self.default_inner.is_some()
+                        && !res.key_handled
+                        && key.code == KeyCode::Char('q')
+                    {
+                        return self.quit(terminal);
                     }
                 }
             }
         }
     }
 }
+#[cfg(test)]
+mod application_test {
+    use super::Created;
+    use crate::common::Editor;
+    use ratatui::{Terminal, backend::TestBackend, layout::Rect};
+    use std::path::PathBuf;
+    #[test]
+    fn test_created_panel_is_clamped() {
+        assert_eq!(Created::height(0, 30), 0);
+        assert_eq!(Created::height(3, 30), 5);
+        assert_eq!(Created::height(20, 30), 10);
+        let created: Vec<Created> = (0 .. 20)
+            .map(|index| Created {
+                project_path: PathBuf::from(format!("/tmp/project-{index}")),
+                main_file:    String::new(),
+                editor:       Editor::NotNeed,
+                open_on_quit: false,
+            })
+            .collect();
+        let mut terminal = Terminal::new(TestBackend::new(20, 30)).unwrap();
+        terminal
+            .draw(|f| {
+                let height = Created::height(created.len(), f.area().height);
+                Created::render(&created, f, Rect::new(0, 0, 20, height));
+            })
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("project-19"));
+        assert!(!screen.contains("project-0"));
+    }
+}
@@ -1,215 +1,100 @@
 use super::{
-    Inner, InnerCommonState, InnerField, InnerFieldMapping, InnerHandleKeyEventOutput,
-    InnerTipLabel, RadioOption, handle_inner_keyevent,
+    Inner, InnerCommonState, InnerField, InnerHandleKeyEventOutput, RadioOption, RadioOptionTrait,
+    handle_inner_keyevent, handle_inner_paste, inner_restore, inner_summary, render_inner_form,
 };
 use crate::{
-    EnumFunc, InnerState, RadioOption,
-    common::{Editor, Vcs},
+    AppContext, InnerForm, InnerState,
+    common::{Editor, EditorSettings, Requirement, Vcs, expand_path, validate_directory},
+    generate::{
+        CargoOptions, Options,
+        cargo::{Edition, ProjectType},
+    },
 };
-use anyhow::Result;
 use heck::ToSnakeCase;
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::FromPrimitive;
-use ratatui::{
-    Frame,
-    crossterm::event::KeyEvent,
-    layout::Layout,
-    prelude::Rect,
-    style::Color,
-    widgets::{Block, BorderType, Borders, Paragraph},
-};
-use ratatui_macros::constraints;
-use std::{env, fmt::Debug, fs, path::PathBuf, process::Command};
-use strum_macros::{Display, EnumIter};
-#[derive(Clone, Copy, Display, EnumIter, FromPrimitive, ToPrimitive)]
-enum CargoField {
-    Name,
-    ProjectType,
-    Edition,
-    Editor,
-    Vcs,
-    Path,
-}
+use ratatui::{Frame, crossterm::event::KeyEvent, prelude::Rect};
+use std::{path::PathBuf, sync::Arc};
+use strum::IntoEnumIterator;
 impl InnerField for CargoField {
-    fn validate_string(self, value: &mut str) -> String {
+    fn validate_string(self, value: &mut str, _: &AppContext) -> String {
         if value.is_empty() {
             return format!("{} cannot be empty", self.to_string().to_snake_case());
         }
-        String::new()
+        match self {
+            Self::Path => validate_directory(value),
+            _ => String::new(),
+        }
+    }
+
+    fn is_path(self) -> bool {
+        matches!(self, Self::Path)
     }
 }
-#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, RadioOption, EnumFunc)]
-enum ProjectType {
-    #[default]
-    #[enum_func(args("--name"), main_file("src/main.rs"))]
-    Executable,
-    #[enum_func(args("--lib"), main_file("src/lib.rs"))]
-    Library,
-}
-#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, RadioOption)]
-enum Edition {
-    #[strum(to_string = "2015")]
-    Fifteen,
-    #[strum(to_string = "2018")]
-    Eighteen,
-    #[strum(to_string = "2021")]
-    TwentyOne,
-    #[default]
-    #[strum(to_string = "2024")]
-    TwentyFour,
-}
-#[derive(InnerState, Clone)]
+#[derive(InnerForm, InnerState, Clone)]
 pub(super) struct CargoInner {
-    name:         String,
-    project_type: RadioOption<ProjectType>,
-    edition:      RadioOption<Edition>,
-    editor:       RadioOption<Editor>,
-    vcs:          RadioOption<Vcs>,
-    path:         PathBuf,
-    common_state: InnerCommonState,
+    #[field(label = "name", tip = "Please input the name of this project", kind = text)]
+    name:            String,
+    #[field(label = "project_type", tip = "Use arrow keys to select project_type", kind = radio)]
+    project_type:    RadioOption<ProjectType>,
+    #[field(label = "edition", tip = "Use arrow keys to select edition", kind = radio)]
+    edition:         RadioOption<Edition>,
+    #[field(label = "editor", tip = "Use arrow keys to select editor", kind = radio)]
+    editor:          RadioOption<Editor>,
+    #[field(
+        label = "editor_settings",
+        tip = "Use arrow keys to select whether to generate editor settings",
+        kind = radio
+    )]
+    editor_settings: RadioOption<EditorSettings>,
+    #[field(label = "vcs", tip = "Use arrow keys to select vcs tool", kind = radio)]
+    vcs:             RadioOption<Vcs>,
+    #[field(
+        label = "path",
+        tip = "Please input the path of this project, Tab completes it, Ctrl-O browses",
+        kind = text
+    )]
+    path:            String,
+    common_state:    InnerCommonState,
 }
 impl CargoInner {
-    pub(super) fn new() -> Self {
+    pub(super) fn new(context: &Arc<AppContext>) -> Self {
         Self {
-            name:         String::new(),
-            project_type: RadioOption::default(),
-            edition:      RadioOption::default(),
-            editor:       RadioOption::default(),
-            vcs:          RadioOption::default(),
-            path:         env::current_dir().unwrap(),
-            common_state: InnerCommonState::new::<CargoField>(),
-        }
-    }
-}
-impl InnerFieldMapping<CargoField> for CargoInner {
-    fn get_focus_field_mut(&mut self, field: CargoField) -> Option<&mut String> {
-        match field {
-            CargoField::Name => Some(&mut self.name),
-            _ => None,
+            name:            String::new(),
+            project_type:    RadioOption::new(context),
+            edition:         RadioOption::new(context),
+            editor:          RadioOption::new(context),
+            editor_settings: RadioOption::new(context),
+            vcs:             RadioOption::new(context),
+            path:            context.config.work_dir.display().to_string(),
+            common_state:    InnerCommonState::new::<CargoField>(context),
         }
     }
 
-    fn get_field(&self, field: CargoField) -> &dyn std::fmt::Debug {
-        match field {
-            CargoField::Name => &self.name,
-            CargoField::ProjectType => &self.project_type.value,
-            CargoField::Edition => &self.edition.value,
-            CargoField::Editor => &self.editor.value,
-            CargoField::Vcs => &self.vcs.value,
-            CargoField::Path => &self.path,
-        }
+    pub(super) fn unmet_requirements(context: &AppContext) -> Vec<String> {
+        RadioOption::<Edition>::new(context).unmet_requirements()
     }
 
-    fn get_radio(&mut self, field: CargoField) -> Option<&mut dyn super::RadioOptionTrait> {
-        match field {
-            CargoField::ProjectType => Some(&mut self.project_type),
-            CargoField::Edition => Some(&mut self.edition),
-            CargoField::Editor => Some(&mut self.editor),
-            CargoField::Vcs => Some(&mut self.vcs),
-            _ => None,
-        }
-    }
-}
-impl InnerTipLabel for CargoInner {
-    fn tips() -> &'static [&'static str] {
-        &[
-            "Please input the name of this project",
-            "Use arrow keys to select project_type",
-            "Use arrow keys to select edition",
-            "Use arrow keys to select editor",
-            "Use arrow keys to select vcs tool",
-            "Please input the path of this project",
-        ]
+    /// The tool versions the options are checked against.
+    pub(super) fn requirements() -> Vec<Requirement> {
+        Edition::iter()
+            .filter_map(|edition| edition.requires())
+            .map(Requirement::new)
+            .collect()
     }
 
-    fn labels() -> &'static [&'static str] {
-        &["name", "project_type", "edition", "editor", "vcs", "path"]
+    fn options(&self) -> CargoOptions {
+        CargoOptions {
+            name:            self.name.clone(),
+            project_type:    self.project_type.value,
+            edition:         self.edition.value,
+            vcs:             self.vcs.value,
+            editor_settings: (self.editor_settings.value == EditorSettings::Generate)
+                .then_some(self.editor.value),
+        }
     }
 }
 impl Inner for CargoInner {
     fn render(&mut self, f: &mut Frame, focus_right_side: bool, area: Rect) {
-        let labels = Self::labels();
-        // 表单布局 - 垂直排列输入框
-        let form_layout = Layout::vertical(constraints![==5;6]).split(area);
-        let split_line_layout = Layout::horizontal(constraints![==1/2;2]);
-        let split_label_input_layout = Layout::horizontal(constraints![==20,>=0]);
-        let split_tip_input_error_layout = Layout::vertical(constraints![<=1,<=3,<=1]);
-        for i in (0 .. labels.len()).step_by(2) {
-            let line_layout = split_line_layout.split(form_layout[i / 2]);
-            for side in 0 .. 2 {
-                let index = i + side;
-                if index == labels.len() {
-                    break;
-                }
-                let side_line_layout = line_layout[side];
-                let label_input_area = split_label_input_layout.split(side_line_layout);
-                f.render_widget(
-                    Paragraph::new(labels[index]).centered().block(
-                        Block::default()
-                            .borders(Borders::all())
-                            .border_type(BorderType::Thick),
-                    ),
-                    split_tip_input_error_layout.split(label_input_area[0])[1],
-                );
-                let focus_block = Block::new()
-                    .borders(Borders::ALL)
-                    .border_style(
-                        if index == self.common_state.focus_index.value && focus_right_side {
-                            Color::Red
-                        } else {
-                            Color::default()
-                        },
-                    )
-                    .border_type(BorderType::Thick);
-                if focus_right_side && index == self.common_state.focus_index.value {
-                    f.render_widget(
-                        Paragraph::new(Self::tips()[index])
-                            .style(Color::Blue)
-                            .centered(),
-                        split_tip_input_error_layout.split(label_input_area[1])[0],
-                    );
-                }
-                let field = CargoField::from_usize(index).unwrap();
-                if let Some(r) = self.get_radio(field) {
-                    f.render_widget(
-                        Paragraph::new(
-                            (0 .. r.length())
-                                .map(|curr| r.get_symbol(curr))
-                                .collect::<Vec<String>>()
-                                .join("    "),
-                        )
-                        .centered()
-                        .block(focus_block),
-                        split_tip_input_error_layout.split(label_input_area[1])[1],
-                    );
-                    continue;
-                }
-                let field_value = self.get_field(field);
-                let field_string_value = format!("{field_value:?}").replace('"', "");
-                f.render_widget(
-                    Paragraph::new(if field_string_value.is_empty() {
-                        format!("Please input {}", labels[index])
-                    } else {
-                        field_string_value.clone()
-                    })
-                    .centered()
-                    .style(if field_string_value.is_empty() {
-                        Color::Gray
-                    } else {
-                        Color::default()
-                    })
-                    .block(focus_block),
-                    split_tip_input_error_layout.split(label_input_area[1])[1],
-                );
-                if !self.common_state.error_messages[index].is_empty() {
-                    f.render_widget(
-                        Paragraph::new(self.common_state.error_messages[index].clone())
-                            .style(Color::Red),
-                        split_tip_input_error_layout.split(label_input_area[1])[2],
-                    );
-                }
-            }
-        }
+        render_inner_form::<Self, CargoField>(self, f, focus_right_side, area);
     }
 
     fn bottom_help_message(&self) -> String {
@@ -221,21 +106,40 @@ impl Inner for CargoInner {
         handle_inner_keyevent(self, key)
     }
 
-    fn create_and_edit(&self) -> Result<()> {
-        let project_path = self.path.join(&self.name);
-        fs::create_dir_all(&project_path)?;
-        self.vcs.value.init_vcs_repo(&self.name, &self.path)?;
-        Command::new("cargo")
-            .arg("init")
-            .arg(self.project_type.value.args())
-            .arg(&self.name)
-            .arg("--edition")
-            .arg(format!("{}", self.edition.value))
-            .current_dir(&project_path)
-            .status()?;
-        self.editor
-            .value
-            .run(project_path, self.project_type.value.main_file())?;
-        Ok(())
+    fn handle_paste(&mut self, text: &str) {
+        handle_inner_paste(self, text);
+    }
+
+    fn summary(&mut self) -> Vec<(String, String)> {
+        inner_summary::<Self, CargoField>(self)
+    }
+
+    fn restore(&mut self, values: &[(String, String)]) {
+        inner_restore::<Self, CargoField>(self, values);
+    }
+
+    fn generation(&self) -> Option<(Options, PathBuf)> {
+        Some((self.options().into(), expand_path(&self.path)))
+    }
+
+    fn editor(&self) -> Editor {
+        self.editor.value
+    }
+}
+#[cfg(test)]
+mod cargo_test {
+    use super::{CargoInner, Inner};
+    use crate::AppContext;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::sync::Arc;
+    #[test]
+    fn test_text_fields_take_keys() {
+        let mut inner = CargoInner::new(&Arc::new(AppContext::default()));
+        let mut press = |code| inner.handle_keyevent(KeyEvent::new(code, KeyModifiers::NONE));
+        assert!(press(KeyCode::Char('q')).key_handled);
+        assert!(!press(KeyCode::Tab).key_handled);
+        // project_type 是单选, q 没有用处
+        assert!(!press(KeyCode::Char('q')).key_handled);
+        assert_eq!(inner.name, "q");
     }
 }
@@ -1,255 +1,166 @@
 use super::{
-    Inner, InnerCommonState, InnerField, InnerFieldMapping, InnerHandleKeyEventOutput,
-    InnerTipLabel, RadioOption, RadioOptionTrait, handle_inner_keyevent,
+    Inner, InnerCommonState, InnerField, InnerHandleKeyEventOutput, PrepareRecv, PrepareTrait,
+    RadioOption, RadioOptionTrait, handle_inner_keyevent, handle_inner_paste, inner_restore,
+    inner_summary, render_inner_form,
 };
 use crate::{
-    EnumFunc, InnerState, RadioOption,
-    common::{Editor, Vcs},
+    AppContext, InnerForm, InnerState,
+    common::{
+        Editor, EditorSettings, Executable, Requirement, Vcs, Version, expand_path,
+        validate_directory,
+    },
+    generate::{
+        CmakeOptions, Options,
+        cmake::{Compilers, Language, ProjectType},
+    },
 };
-use anyhow::Result;
 use heck::ToSnakeCase;
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::FromPrimitive;
-use ratatui::{
-    Frame,
-    crossterm::event::KeyEvent,
-    layout::Layout,
-    prelude::Rect,
-    style::Color,
-    widgets::{Block, BorderType, Borders, Paragraph},
-};
-use ratatui_macros::constraints;
-use std::{env, fmt::Debug, fs, path::PathBuf};
-use strum_macros::{Display, EnumIter};
-#[derive(Clone, Copy, Display, EnumIter, FromPrimitive, ToPrimitive)]
-enum CmakeField {
-    Name,
-    ProjectVersion,
-    ProjectType,
-    Language,
-    LanguageVersion,
-    Editor,
-    Vcs,
-    Path,
-}
+use ratatui::{Frame, crossterm::event::KeyEvent, prelude::Rect};
+use std::{path::PathBuf, sync::Arc};
+use strum::IntoEnumIterator;
 impl InnerField for CmakeField {
-    fn validate_string(self, value: &mut str) -> String {
+    fn validate_string(self, value: &mut str, context: &AppContext) -> String {
         if value.is_empty() {
             return format!("{} cannot be empty", self.to_string().to_snake_case());
         }
-        String::new()
-    }
-}
-#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, RadioOption)]
-enum ProjectType {
-    #[default]
-    Executable,
-    Library,
-}
-#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, RadioOption, EnumFunc)]
-enum Language {
-    #[default]
-    #[enum_func(main_file("main.c"), standard("C"))]
-    C,
-    #[enum_func(main_file("main.cpp"), standard("CXX"))]
-    Cpp,
-}
-impl Language {
-    fn main_file_content(self) -> String {
         match self {
-            Self::C => {
-                "\
-                #include <stdio.h>\n\
-                \n\
-                int main() {\n\
-                \tprintf(\"Hello, World!\");\n\
-                \treturn 0;\n\
-                }\n"
-            }
-            Self::Cpp => {
-                "\
-                #include <iostream>\n\
-                \n\
-                int main() {\n\
-                \tstd::cout << \"Hello, World!\" << std::endl;\n\
-                \treturn 0;\n\
-                }\n"
+            Self::CmakeMinimumRequired => match value.parse::<Version>() {
+                Ok(minimum) => Version::installed(context.executables(), "cmake")
+                    .filter(|installed| *installed < minimum)
+                    .map(|installed| format!("installed cmake {installed} is older than {value}"))
+                    .unwrap_or_default(),
+                Err(_) => "cmake_minimum_required must be a version like 3.20".to_string(),
+            },
+            // 语言在另一个字段里, 这里只要求有语言认得这个标准,
+            // 组合是否成立在确认前检查
+            Self::LanguageStandardVersion => {
+                if Language::iter().any(|language| language.standard_requirement(value).is_ok()) {
+                    String::new()
+                } else {
+                    format!("language_version {value} is not a C nor a C++ standard")
+                }
             }
+            Self::Path => validate_directory(value),
+            _ => String::new(),
         }
-        .to_string()
+    }
+
+    fn is_path(self) -> bool {
+        matches!(self, Self::Path)
     }
 }
-#[derive(Clone, InnerState)]
+#[derive(Clone, InnerForm, InnerState)]
 pub(super) struct CmakeInner {
+    #[field(label = "name", tip = "Please input the name of this project", kind = text)]
     name:                      String,
+    #[field(
+        label = "cmake_minimum_required",
+        tip = "Please input the cmake_minimum_required of this project",
+        kind = text
+    )]
     cmake_minimum_required:    String,
+    #[field(label = "project_type", tip = "Use arrow keys to select project_type", kind = radio)]
     project_type:              RadioOption<ProjectType>,
+    #[field(label = "language", tip = "Use arrow keys to select language", kind = radio)]
     language:                  RadioOption<Language>,
+    #[field(
+        label = "language_version",
+        tip = "Please input the language_version of this project",
+        kind = text
+    )]
     language_standard_version: String,
+    #[field(label = "editor", tip = "Use arrow keys to select editor", kind = radio)]
     editor:                    RadioOption<Editor>,
+    #[field(
+        label = "editor_settings",
+        tip = "Use arrow keys to select whether to generate editor settings",
+        kind = radio
+    )]
+    editor_settings:           RadioOption<EditorSettings>,
+    #[field(label = "vcs", tip = "Use arrow keys to select vcs tool", kind = radio)]
     vcs:                       RadioOption<Vcs>,
-    path:                      PathBuf,
+    #[field(
+        label = "path",
+        tip = "Please input the path of this project, Tab completes it, Ctrl-O browses",
+        kind = text
+    )]
+    path:                      String,
     common_state:              InnerCommonState,
 }
 impl CmakeInner {
-    pub(super) fn new() -> Self {
+    pub(super) fn new(context: &Arc<AppContext>) -> Self {
         Self {
             name:                      String::new(),
             cmake_minimum_required:    String::new(),
-            project_type:              RadioOption::default(),
-            language:                  RadioOption::default(),
+            project_type:              RadioOption::new(context),
+            language:                  RadioOption::new(context),
             language_standard_version: String::new(),
-            editor:                    RadioOption::default(),
-            vcs:                       RadioOption::default(),
-            path:                      env::current_dir().unwrap(),
-            common_state:              InnerCommonState::new::<CmakeField>(),
+            editor:                    RadioOption::new(context),
+            editor_settings:           RadioOption::new(context),
+            vcs:                       RadioOption::new(context),
+            path:                      context.config.work_dir.display().to_string(),
+            common_state:              InnerCommonState::new::<CmakeField>(context),
         }
     }
-}
-impl InnerFieldMapping<CmakeField> for CmakeInner {
-    fn get_focus_field_mut(&mut self, field: CmakeField) -> Option<&mut String> {
-        match field {
-            CmakeField::Name => Some(&mut self.name),
-            CmakeField::ProjectVersion => Some(&mut self.cmake_minimum_required),
-            CmakeField::LanguageVersion => Some(&mut self.language_standard_version),
-            _ => None,
-        }
+
+    pub(super) fn unmet_requirements(context: &AppContext) -> Vec<String> {
+        RadioOption::<Language>::new(context).unmet_requirements()
     }
 
-    fn get_field(&self, field: CmakeField) -> &dyn Debug {
-        match field {
-            CmakeField::Name => &self.name,
-            CmakeField::ProjectVersion => &self.cmake_minimum_required,
-            CmakeField::ProjectType => &self.project_type.value,
-            CmakeField::Language => &self.language.value,
-            CmakeField::LanguageVersion => &self.language_standard_version,
-            CmakeField::Editor => &self.editor.value,
-            CmakeField::Vcs => &self.vcs.value,
-            CmakeField::Path => &self.path,
-        }
+    /// The tool versions the options are checked against.
+    pub(super) fn requirements() -> Vec<Requirement> {
+        Language::iter()
+            .flat_map(|language| {
+                language
+                    .standards()
+                    .filter_map(move |standard| language.standard_requirement(standard).ok())
+            })
+            .collect()
     }
 
-    fn get_radio(&mut self, field: CmakeField) -> Option<&mut dyn RadioOptionTrait> {
-        match field {
-            CmakeField::ProjectType => Some(&mut self.project_type),
-            CmakeField::Language => Some(&mut self.language),
-            CmakeField::Editor => Some(&mut self.editor),
-            CmakeField::Vcs => Some(&mut self.vcs),
-            _ => None,
+    fn options(&self) -> CmakeOptions {
+        CmakeOptions {
+            name:                      self.name.clone(),
+            cmake_minimum_required:    self.cmake_minimum_required.clone(),
+            project_type:              self.project_type.value,
+            language:                  self.language.value,
+            language_standard_version: self.language_standard_version.clone(),
+            vcs:                       self.vcs.value,
+            editor_settings:           (self.editor_settings.value == EditorSettings::Generate)
+                .then_some(self.editor.value),
         }
     }
 }
-impl InnerTipLabel for CmakeInner {
-    fn tips() -> &'static [&'static str] {
-        &[
-            "Please input the name of this project",
-            "Please input the cmake_minimum_required of this project",
-            "Use arrow keys to select project_type",
-            "Use arrow keys to select language",
-            "Use arrow keys to select language_version",
-            "Use arrow keys to select editor",
-            "Use arrow keys to select vcs tool",
-            "Please input the path of this project",
-        ]
+impl PrepareTrait for CmakeInner {
+    fn header() -> &'static str {
+        "CMake preparing"
+    }
+
+    async fn prepare(context: Arc<AppContext>, mut recv: PrepareRecv) -> bool {
+        let mut found = vec![];
+        for language in Language::iter() {
+            if language
+                .compilers()
+                .iter()
+                .any(|compiler| Executable::resolve(compiler).is_some())
+            {
+                found.push(language);
+            }
+            recv.send_ok();
+        }
+        context.set_cache(Compilers(found));
+        true
     }
 
-    fn labels() -> &'static [&'static str] {
-        &[
-            "name",
-            "cmake_minimum_required",
-            "project_type",
-            "language",
-            "language_version",
-            "editor",
-            "vcs",
-            "path",
-        ]
+    fn descs() -> Vec<String> {
+        Language::iter()
+            .map(|language| format!("Detecting {language} compiler"))
+            .collect()
     }
 }
 impl Inner for CmakeInner {
     fn render(&mut self, f: &mut Frame, focus_right_side: bool, area: Rect) {
-        let labels = Self::labels();
-        // 表单布局 - 垂直排列输入框
-        let form_layout = Layout::vertical(constraints![==5;6]).split(area);
-        let split_line_layout = Layout::horizontal(constraints![==1/2;2]);
-        let split_label_input_layout = Layout::horizontal(constraints![==20,>=0]);
-        let split_tip_input_error_layout = Layout::vertical(constraints![<=1,<=3,<=1]);
-        for i in (0 .. labels.len()).step_by(2) {
-            let line_layout = split_line_layout.split(form_layout[i / 2]);
-            for side in 0 .. 2 {
-                let index = i + side;
-                if index == labels.len() {
-                    break;
-                }
-                let side_line_layout = line_layout[side];
-                let label_input_area = split_label_input_layout.split(side_line_layout);
-                f.render_widget(
-                    Paragraph::new(labels[index]).centered().block(
-                        Block::default()
-                            .borders(Borders::all())
-                            .border_type(BorderType::Thick),
-                    ),
-                    split_tip_input_error_layout.split(label_input_area[0])[1],
-                );
-                let focus_block = Block::new()
-                    .borders(Borders::ALL)
-                    .border_style(
-                        if index == self.common_state.focus_index.value && focus_right_side {
-                            Color::Red
-                        } else {
-                            Color::default()
-                        },
-                    )
-                    .border_type(BorderType::Thick);
-                if focus_right_side && index == self.common_state.focus_index.value {
-                    f.render_widget(
-                        Paragraph::new(Self::tips()[index])
-                            .style(Color::Blue)
-                            .centered(),
-                        split_tip_input_error_layout.split(label_input_area[1])[0],
-                    );
-                }
-                let field = CmakeField::from_usize(index).unwrap();
-                if let Some(r) = self.get_radio(field) {
-                    f.render_widget(
-                        Paragraph::new(
-                            (0 .. r.length())
-                                .map(|curr| r.get_symbol(curr))
-                                .collect::<Vec<String>>()
-                                .join("    "),
-                        )
-                        .centered()
-                        .block(focus_block),
-                        split_tip_input_error_layout.split(label_input_area[1])[1],
-                    );
-                    continue;
-                }
-                let field_value = self.get_field(field);
-                let field_string_value = format!("{field_value:?}").replace('"', "");
-                f.render_widget(
-                    Paragraph::new(if field_string_value.is_empty() {
-                        format!("Please input {}", labels[index])
-                    } else {
-                        field_string_value.clone()
-                    })
-                    .centered()
-                    .style(if field_string_value.is_empty() {
-                        Color::Gray
-                    } else {
-                        Color::default()
-                    })
-                    .block(focus_block),
-                    split_tip_input_error_layout.split(label_input_area[1])[1],
-                );
-                if !self.common_state.error_messages[index].is_empty() {
-                    f.render_widget(
-                        Paragraph::new(self.common_state.error_messages[index].clone())
-                            .style(Color::Red),
-                        split_tip_input_error_layout.split(label_input_area[1])[2],
-                    );
-                }
-            }
-        }
+        render_inner_form::<Self, CmakeField>(self, f, focus_right_side, area);
     }
 
     fn bottom_help_message(&self) -> String {
@@ -261,33 +172,23 @@ impl Inner for CmakeInner {
         handle_inner_keyevent(self, key)
     }
 
-    fn create_and_edit(&self) -> Result<()> {
-        let project_path = self.path.join(&self.name);
-        fs::create_dir_all(&project_path)?;
-        self.vcs.value.init_vcs_repo(&self.name, &self.path)?;
-        let cmake_lists = format!(
-            "\
-                cmake_minimum_required(VERSION {})\n\
-                project({})\n\
-                \n\
-                set(CMAKE_{}_STANDARD {})\n\
-                \n\
-                add_{}(${{PROJECT_NAME}} {})\n",
-            self.cmake_minimum_required,
-            self.name,
-            self.language.value.standard(),
-            self.language_standard_version,
-            self.project_type.value.to_string().to_lowercase(),
-            self.language.value.main_file()
-        );
-        fs::write(project_path.join("CMakeLists.txt"), cmake_lists)?;
-        fs::write(
-            project_path.join(self.language.value.main_file()),
-            self.language.value.main_file_content(),
-        )?;
-        self.editor
-            .value
-            .run(project_path, self.language.value.main_file())?;
-        Ok(())
+    fn handle_paste(&mut self, text: &str) {
+        handle_inner_paste(self, text);
+    }
+
+    fn summary(&mut self) -> Vec<(String, String)> {
+        inner_summary::<Self, CmakeField>(self)
+    }
+
+    fn restore(&mut self, values: &[(String, String)]) {
+        inner_restore::<Self, CmakeField>(self, values);
+    }
+
+    fn generation(&self) -> Option<(Options, PathBuf)> {
+        Some((self.options().into(), expand_path(&self.path)))
+    }
+
+    fn editor(&self) -> Editor {
+        self.editor.value
     }
 }
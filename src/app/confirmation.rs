@@ -0,0 +1,170 @@
+use crate::generate::Report;
+use anyhow::Result;
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use ratatui_macros::constraints;
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum ConfirmationOutput {
+    Confirm,
+    Back,
+}
+/// Sums the chosen values and the actions up before anything is created.
+pub(super) struct Confirmation {
+    lines:     Vec<Line<'static>>,
+    scroll:    u16,
+    confirmed: bool,
+    /// Nothing can be confirmed when the actions could not be planned.
+    error:     bool,
+}
+impl Confirmation {
+    pub(super) fn new(summary: Vec<(String, String)>, report: Result<Report>) -> Self {
+        let section = |title: String| {
+            Line::styled(
+                title,
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+        let width = summary
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or_default();
+        let mut lines: Vec<Line> = summary
+            .into_iter()
+            .map(|(label, value)| Line::raw(format!("  {label:<width$}  {value}")))
+            .collect();
+        let error = report.is_err();
+        match report {
+            Ok(report) => {
+                lines.push(Line::default());
+                lines.push(section(format!("Target {}", report.project_path.display())));
+                lines.extend(
+                    report
+                        .actions()
+                        .into_iter()
+                        .map(|action| Line::raw(format!("  {action}"))),
+                );
+            }
+            Err(error) => {
+                lines.push(Line::default());
+                lines.push(Line::styled(error.to_string(), Color::Red));
+            }
+        }
+        Self {
+            lines,
+            scroll: 0,
+            // 默认停在 Back, 误按 Enter 不会直接创建
+            confirmed: false,
+            error,
+        }
+    }
+
+    pub(super) fn handle_keyevent(&mut self, key: KeyEvent) -> Option<ConfirmationOutput> {
+        match key.code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab if !self.error => {
+                self.confirmed = !self.confirmed;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                let max = u16::try_from(self.lines.len().saturating_sub(1)).unwrap_or(u16::MAX);
+                self.scroll = (self.scroll + 1).min(max);
+            }
+            KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Enter => {
+                return Some(if self.confirmed {
+                    ConfirmationOutput::Confirm
+                } else {
+                    ConfirmationOutput::Back
+                });
+            }
+            KeyCode::Esc => return Some(ConfirmationOutput::Back),
+            _ => {}
+        }
+        None
+    }
+
+    fn button(label: &'static str, focused: bool) -> Span<'static> {
+        Span::styled(
+            format!("[ {label} ]"),
+            if focused {
+                Style::default().fg(Color::Black).bg(Color::LightBlue)
+            } else {
+                Style::default()
+            },
+        )
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect) {
+        let height = u16::try_from(self.lines.len() + 4)
+            .unwrap_or(u16::MAX)
+            .min(area.height);
+        let modal_area = Layout::horizontal(constraints![==10%,>=0,==10%]).split(area)[1];
+        let modal_area = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .split(modal_area)[0];
+        frame.render_widget(Clear, modal_area);
+        let block = Block::default()
+            .title("Create this project?")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::LightBlue));
+        let inner_area = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+        let split_area = Layout::vertical(constraints![>=0,==1]).split(inner_area);
+        frame.render_widget(
+            Paragraph::new(self.lines.clone()).scroll((self.scroll, 0)),
+            split_area[0],
+        );
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Self::button("Confirm", self.confirmed),
+                Span::raw("    "),
+                Self::button("Back", !self.confirmed),
+            ]))
+            .centered(),
+            split_area[1],
+        );
+    }
+}
+#[cfg(test)]
+mod confirmation_test {
+    use super::{Confirmation, ConfirmationOutput};
+    use crate::generate::{CmakeOptions, Report, dry_run};
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::path::Path;
+    fn press(confirmation: &mut Confirmation, code: KeyCode) -> Option<ConfirmationOutput> {
+        confirmation.handle_keyevent(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+    #[test]
+    fn test_focus_starts_on_back() {
+        let summary = vec![("name".to_string(), "demo".to_string())];
+        let mut confirmation = Confirmation::new(summary.clone(), Ok(Report::default()));
+        assert!(press(&mut confirmation, KeyCode::Enter) == Some(ConfirmationOutput::Back));
+        press(&mut confirmation, KeyCode::Left);
+        assert!(press(&mut confirmation, KeyCode::Enter) == Some(ConfirmationOutput::Confirm));
+        press(&mut confirmation, KeyCode::Tab);
+        assert!(press(&mut confirmation, KeyCode::Enter) == Some(ConfirmationOutput::Back));
+        // 目标目录已有内容时, 只能返回
+        let report = dry_run(
+            CmakeOptions::new("src"),
+            Path::new(env!("CARGO_MANIFEST_DIR")),
+        );
+        let mut confirmation = Confirmation::new(summary, report);
+        assert!(
+            confirmation
+                .lines
+                .last()
+                .unwrap()
+                .to_string()
+                .ends_with("src already exists and is not empty")
+        );
+        press(&mut confirmation, KeyCode::Left);
+        assert!(press(&mut confirmation, KeyCode::Enter) == Some(ConfirmationOutput::Back));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    AppContext,
+    common::{Editor, LoopNumber},
+    generate::{GenerationEvent, Options, Report, generate_logged, generate_with},
+};
+use anyhow::Result;
+use num_traits::ToPrimitive;
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Layout, Rect},
+    style::{Color, Style, palette::tailwind},
+    text::{Line, Span},
+    widgets::{Block, Borders, LineGauge, Paragraph, Wrap},
+};
+use ratatui_macros::constraints;
+use std::{path::PathBuf, sync::Arc};
+use strum_macros::Display;
+use tokio::sync::{mpsc, oneshot};
+/// What is picked once the project is created, or failed to be.
+#[derive(Clone, Copy, Display, PartialEq)]
+pub(super) enum CreationOutput {
+    #[strum(to_string = "Open editor now")]
+    OpenEditor,
+    #[strum(to_string = "Create another")]
+    CreateAnother,
+    #[strum(to_string = "Back to the form")]
+    BackToForm,
+    Quit,
+}
+/// Follows a project being created in the background, then offers what to do
+/// next.
+pub(super) struct Creation {
+    project_path: PathBuf,
+    editor:       Editor,
+    total:        usize,
+    steps:        Vec<String>,
+    log:          Vec<String>,
+    /// How many lines the log is scrolled up from its end.
+    scroll_back:  usize,
+    events:       mpsc::UnboundedReceiver<GenerationEvent>,
+    done:         oneshot::Receiver<Result<Report>>,
+    result:       Option<Result<Report>>,
+    /// What failed around a project created anyway, shown instead of an
+    /// error.
+    warning:      String,
+    outputs:      Vec<CreationOutput>,
+    selected:     LoopNumber,
+}
+impl Creation {
+    /// Starts creating the project on a blocking thread, as it runs external
+    /// commands.
+    pub(super) fn spawn(
+        context: Arc<AppContext>,
+        options: Options,
+        target: PathBuf,
+        editor: Editor,
+    ) -> Self {
+        let plan = generate_with(&context, options.clone(), &target, true);
+        let project_path = plan
+            .as_ref()
+            .map_or_else(|_| target.clone(), |plan| plan.project_path.clone());
+        let total = plan.map_or(0, |plan| plan.actions().len());
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (done_tx, done) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let _ = done_tx.send(generate_logged(&context, options, &target, events_tx));
+        });
+        Self {
+            project_path,
+            editor,
+            total,
+            steps: vec![],
+            log: vec![],
+            scroll_back: 0,
+            events,
+            done,
+            result: None,
+            warning: String::new(),
+            outputs: vec![],
+            selected: LoopNumber::new(1),
+        }
+    }
+
+    /// Takes in every event sent since the last update, and the result once
+    /// the creation is over, returning whether it is over since this update.
+    pub(super) fn update(&mut self) -> bool {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                GenerationEvent::Step(step) => {
+                    self.log.push(format!("$ {step}"));
+                    self.steps.push(step);
+                }
+                GenerationEvent::Output(line) => self.log.push(line),
+            }
+        }
+        if self.result.is_none()
+            && let Ok(result) = self.done.try_recv()
+        {
+            self.outputs = match &result {
+                Ok(_) if self.editor == Editor::NotNeed => {
+                    vec![CreationOutput::CreateAnother, CreationOutput::Quit]
+                }
+                Ok(_) => vec![
+                    CreationOutput::CreateAnother,
+                    CreationOutput::OpenEditor,
+                    CreationOutput::Quit,
+                ],
+                Err(_) => vec![CreationOutput::BackToForm, CreationOutput::Quit],
+            };
+            self.selected = LoopNumber::new(self.outputs.len());
+            self.result = Some(result);
+            return true;
+        }
+        false
+    }
+
+    pub(super) fn warn(&mut self, warning: String) {
+        self.warning = warning;
+    }
+
+    pub(super) fn report(&self) -> Option<&Report> {
+        self.result.as_ref()?.as_ref().ok()
+    }
+
+    pub(super) fn editor(&self) -> Editor {
+        self.editor
+    }
+
+    pub(super) fn done(&self) -> bool {
+        self.result.is_some()
+    }
+
+    pub(super) fn handle_keyevent(&mut self, key: KeyEvent) -> Option<CreationOutput> {
+        match key.code {
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.scroll_back = (self.scroll_back + 1).min(self.log.len().saturating_sub(1));
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.scroll_back = self.scroll_back.saturating_sub(1);
+            }
+            KeyCode::Left | KeyCode::BackTab if self.done() => self.selected = self.selected.prev(),
+            KeyCode::Right | KeyCode::Tab if self.done() => self.selected = self.selected.next(),
+            KeyCode::Enter if self.done() => return self.outputs.get(self.selected.value).copied(),
+            _ => {}
+        }
+        None
+    }
+
+    fn ratio(&self) -> f64 {
+        if self.report().is_some() || self.total == 0 {
+            return if self.done() { 1. } else { 0. };
+        }
+        (self.steps.len().to_f64().unwrap() / self.total.to_f64().unwrap()).min(1.)
+    }
+
+    fn header(&self) -> Line<'static> {
+        let path = self.project_path.display();
+        match &self.result {
+            None => Line::raw(format!("Creating {path}")),
+            Some(Ok(_)) => Line::styled(format!("Created {path}"), Color::Green),
+            Some(Err(_)) => Line::styled(format!("Failed to create {path}"), Color::Red),
+        }
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect) {
+        let (error, color) = match &self.result {
+            Some(Err(error)) => (format!("{error:#}"), Color::Red),
+            _ => (self.warning.clone(), Color::Yellow),
+        };
+        let split_area = Layout::vertical(constraints![
+            ==1,
+            ==1,
+            ==1,
+            >=3,
+            ==if error.is_empty() { 0 } else { 3 },
+            ==1
+        ])
+        .split(area);
+        frame.render_widget(Paragraph::new(self.header()).centered(), split_area[0]);
+        frame.render_widget(
+            Paragraph::new(format!(
+                "Step {}/{}: {}",
+                self.steps.len(),
+                self.total.max(self.steps.len()),
+                self.steps.last().map_or("", String::as_str)
+            ))
+            .centered(),
+            split_area[1],
+        );
+        frame.render_widget(
+            LineGauge::default()
+                .filled_symbol("⣿")
+                .unfilled_symbol("⣿")
+                .filled_style(Style::default().fg(tailwind::CYAN.c400))
+                .unfilled_style(Style::default().fg(tailwind::CYAN.c800))
+                .ratio(self.ratio()),
+            split_area[2],
+        );
+        let log_block = Block::default().title("Output").borders(Borders::ALL);
+        let height = usize::from(log_block.inner(split_area[3]).height);
+        let top = self.log.len().saturating_sub(height + self.scroll_back);
+        frame.render_widget(
+            Paragraph::new(
+                self.log
+                    .iter()
+                    .skip(top)
+                    .take(height)
+                    .map(|line| Line::raw(line.clone()))
+                    .collect::<Vec<_>>(),
+            )
+            .block(log_block),
+            split_area[3],
+        );
+        frame.render_widget(
+            Paragraph::new(error).style(color).wrap(Wrap { trim: true }),
+            split_area[4],
+        );
+        let mut buttons = vec![];
+        for (index, output) in self.outputs.iter().enumerate() {
+            if index > 0 {
+                buttons.push(Span::raw("    "));
+            }
+            buttons.push(Span::styled(
+                format!("[ {output} ]"),
+                if index == self.selected.value {
+                    Style::default().fg(Color::Black).bg(Color::LightBlue)
+                } else {
+                    Style::default()
+                },
+            ));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(buttons)).centered(),
+            split_area[5],
+        );
+    }
+}
+#[cfg(test)]
+mod creation_test {
+    use super::{Creation, CreationOutput};
+    use crate::{AppContext, common::Editor, generate::CmakeOptions};
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::{env, fs, process, sync::Arc, time::Duration};
+    async fn finish(mut creation: Creation) -> Creation {
+        while !creation.update() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        creation
+    }
+    #[tokio::test]
+    async fn test_creation_outputs() {
+        let target = env::temp_dir().join(format!("project-setup-creation-{}", process::id()));
+        let context = Arc::new(AppContext::default());
+        let options = CmakeOptions::new("demo");
+        let mut creation = finish(Creation::spawn(
+            context.clone(),
+            options.clone().into(),
+            target.clone(),
+            Editor::NotNeed,
+        ))
+        .await;
+        assert!(creation.report().is_some());
+        assert_eq!(creation.steps.len(), creation.total);
+        assert!((creation.ratio() - 1.).abs() < f64::EPSILON);
+        assert!(creation.outputs == [CreationOutput::CreateAnother, CreationOutput::Quit]);
+        let press = |creation: &mut Creation, code| {
+            creation.handle_keyevent(KeyEvent::new(code, KeyModifiers::NONE))
+        };
+        press(&mut creation, KeyCode::Right);
+        assert!(press(&mut creation, KeyCode::Enter) == Some(CreationOutput::Quit));
+        // 创建失败后可以回到表单
+        let mut creation = finish(Creation::spawn(
+            context,
+            CmakeOptions::new("").into(),
+            target.clone(),
+            Editor::NotNeed,
+        ))
+        .await;
+        assert!(creation.report().is_none());
+        assert!(press(&mut creation, KeyCode::Enter) == Some(CreationOutput::BackToForm));
+        fs::remove_dir_all(target).unwrap();
+    }
+}
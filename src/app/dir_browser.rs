@@ -0,0 +1,108 @@
+use crate::common::subdirectories;
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListState, Paragraph},
+};
+use ratatui_macros::constraints;
+use std::path::PathBuf;
+pub(super) enum DirBrowserOutput {
+    Chosen(PathBuf),
+    Cancel,
+}
+/// A popup walking through directories to pick the one to create the project
+/// in.
+#[derive(Clone)]
+pub(super) struct DirBrowser {
+    dir:      PathBuf,
+    entries:  Vec<String>,
+    selected: usize,
+}
+impl DirBrowser {
+    /// Starts in `start`, or its closest existing ancestor.
+    pub(super) fn new(start: PathBuf) -> Self {
+        let dir = start
+            .ancestors()
+            .find(|dir| dir.is_dir())
+            .map_or_else(|| PathBuf::from("."), ToOwned::to_owned);
+        let mut browser = Self {
+            dir,
+            entries: vec![],
+            selected: 0,
+        };
+        browser.enter(None);
+        browser
+    }
+
+    /// Lists the current directory, selecting `selected` if it is there.
+    fn enter(&mut self, selected: Option<String>) {
+        self.entries = subdirectories(&self.dir);
+        self.selected = selected
+            .and_then(|selected| self.entries.iter().position(|name| *name == selected))
+            .unwrap_or_default();
+    }
+
+    pub(super) fn handle_keyevent(&mut self, key: KeyEvent) -> Option<DirBrowserOutput> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down if !self.entries.is_empty() => {
+                self.selected = (self.selected + 1) % self.entries.len();
+            }
+            KeyCode::Char('k') | KeyCode::Up if !self.entries.is_empty() => {
+                self.selected = (self.selected + self.entries.len() - 1) % self.entries.len();
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                if let Some(name) = self.entries.get(self.selected) {
+                    self.dir.push(name);
+                    self.enter(None);
+                }
+            }
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                let name = self
+                    .dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string());
+                if self.dir.pop() {
+                    self.enter(name);
+                }
+            }
+            KeyCode::Char(' ') => return Some(DirBrowserOutput::Chosen(self.dir.clone())),
+            KeyCode::Esc => return Some(DirBrowserOutput::Cancel),
+            _ => {}
+        }
+        None
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = Layout::horizontal(constraints![==15%,>=0,==15%]).split(area)[1];
+        let popup_area = Layout::vertical(constraints![==10%,>=0,==10%]).split(popup_area)[1];
+        frame.render_widget(Clear, popup_area);
+        let block = Block::default()
+            .title(self.dir.display().to_string())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::LightBlue));
+        let inner_area = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+        let split_area = Layout::vertical(constraints![>=0,==1]).split(inner_area);
+        frame.render_stateful_widget(
+            List::new(self.entries.iter().map(|name| format!("{name}/")))
+                .highlight_style(
+                    Style::default()
+                        .bg(Color::DarkGray)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("» "),
+            split_area[0],
+            &mut ListState::default().with_selected(Some(self.selected)),
+        );
+        frame.render_widget(
+            Paragraph::new(
+                "j/k: move | Enter: open | Backspace: parent | Space: choose | Esc: cancel",
+            )
+            .style(Color::Gray)
+            .centered(),
+            split_area[1],
+        );
+    }
+}
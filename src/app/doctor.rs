@@ -0,0 +1,184 @@
+use super::{CargoInner, CmakeInner, PrepareApplication, SpringBootInner, TemplateInner};
+use crate::{
+    AppContext,
+    common::{Executable, ProjectType, Version, print_table},
+};
+use anyhow::Result;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+#[derive(Serialize)]
+struct ToolReport {
+    name:     String,
+    found:    bool,
+    path:     Option<PathBuf>,
+    version:  Option<String>,
+    disables: Vec<String>,
+}
+/// The environment as `PrepareApplication` sees it, printed without the TUI.
+#[derive(Serialize)]
+pub struct Doctor {
+    tools:              Vec<ToolReport>,
+    unmet_requirements: BTreeMap<String, Vec<String>>,
+    prepare_errors:     Vec<String>,
+}
+impl Doctor {
+    /// Runs every prepare step and collects the report.
+    pub async fn check(context: Arc<AppContext>) -> Self {
+        let prepare_errors = PrepareApplication::prepare_headless(context.clone())
+            .await
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        Self::report(&context, prepare_errors)
+    }
+
+    /// What a missing `cmd` leaves unchecked in the forms, from the same
+    /// requirements the inners check their options against.
+    fn unchecked(cmd: &str) -> Vec<String> {
+        [
+            (ProjectType::Cargo, CargoInner::requirements()),
+            (ProjectType::Cmake, CmakeInner::requirements()),
+            (ProjectType::SpringBoot, SpringBootInner::requirements()),
+        ]
+        .into_iter()
+        .filter(|(_, requirements)| requirements.iter().any(|r| r.cmd() == cmd))
+        .map(|(project_type, _)| format!("{cmd} version checks of project type {project_type}"))
+        .collect()
+    }
+
+    fn report(context: &AppContext, prepare_errors: Vec<String>) -> Self {
+        let tools = Executable::tools()
+            .into_iter()
+            .map(|name| {
+                let executables = context.executables();
+                let found = executables.executable(&name);
+                ToolReport {
+                    found,
+                    path: executables.resolved(&name).map(Into::into),
+                    version: Version::installed(executables, &name)
+                        .map(|version| version.to_string()),
+                    disables: if found {
+                        vec![]
+                    } else {
+                        let mut disables = Executable::disables(&name);
+                        disables.extend(Self::unchecked(&name));
+                        disables
+                    },
+                    name,
+                }
+            })
+            .collect();
+        let unmet_requirements = [
+            (ProjectType::Cargo, CargoInner::unmet_requirements(context)),
+            (ProjectType::Cmake, CmakeInner::unmet_requirements(context)),
+            (
+                ProjectType::Template,
+                TemplateInner::unmet_requirements(context),
+            ),
+            (
+                ProjectType::SpringBoot,
+                SpringBootInner::unmet_requirements(context),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, unmet)| !unmet.is_empty())
+        .map(|(project_type, unmet)| (project_type.to_string(), unmet))
+        .collect();
+        Self {
+            tools,
+            unmet_requirements,
+            prepare_errors,
+        }
+    }
+
+    fn rows(&self) -> Vec<[String; 5]> {
+        self.tools
+            .iter()
+            .map(|tool| {
+                [
+                    tool.name.clone(),
+                    if tool.found { "yes" } else { "no" }.to_string(),
+                    tool.path
+                        .as_ref()
+                        .map_or("-".to_string(), |path| path.display().to_string()),
+                    tool.version.clone().unwrap_or("-".to_string()),
+                    if tool.disables.is_empty() {
+                        "-".to_string()
+                    } else {
+                        tool.disables.join(", ")
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// # Errors
+    /// If the report cannot be serialized to json.
+    pub fn print(&self, json: bool) -> Result<()> {
+        if json {
+            println!("{}", serde_json::to_string_pretty(self)?);
+            return Ok(());
+        }
+        print_table(
+            ["TOOL", "FOUND", "PATH", "VERSION", "DISABLES"],
+            &self.rows(),
+        );
+        for (project_type, unmet) in &self.unmet_requirements {
+            println!("\n{project_type} options disabled by tool versions:");
+            for reason in unmet {
+                println!("  {reason}");
+            }
+        }
+        if !self.prepare_errors.is_empty() {
+            println!("\nFailed prepare steps:");
+            for error in &self.prepare_errors {
+                println!("  {error}");
+            }
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod doctor_test {
+    use super::Doctor;
+    use crate::AppContext;
+    #[test]
+    fn test_missing_tools() {
+        // 未准备的上下文里, 所有工具都找不到
+        let doctor = Doctor::report(&AppContext::default(), vec!["failed".to_string()]);
+        let rows = doctor.rows();
+        let row = |name: &str| rows.iter().find(|row| row[0] == name).unwrap().clone();
+        assert_eq!(
+            row("cargo"),
+            [
+                "cargo".to_string(),
+                "no".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "project type Cargo, cargo version checks of project type Cargo".to_string(),
+            ]
+        );
+        assert_eq!(
+            row("cmake")[4],
+            "cmake version checks of project type Cmake"
+        );
+        assert_eq!(
+            row("java")[4],
+            "java version checks of project type SpringBoot"
+        );
+        let json = serde_json::to_value(&doctor).unwrap();
+        let java = json["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|tool| tool["name"] == "java")
+            .unwrap();
+        assert_eq!(java["found"], false);
+        assert!(java["path"].is_null());
+        assert_eq!(
+            java["disables"],
+            serde_json::json!(["java version checks of project type SpringBoot"])
+        );
+        assert_eq!(json["prepare_errors"], serde_json::json!(["failed"]));
+    }
+}
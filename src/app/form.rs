@@ -0,0 +1,118 @@
+use super::{
+    DerivedFields, FormFields, InnerField, InnerFieldMapping, InnerState, InnerTipLabel,
+    form_layout,
+};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Color,
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+/// Renders the form of an inner from its fields alone, see `render_form`.
+pub(super) fn render_inner_form<T, F>(
+    inner: &mut T,
+    f: &mut Frame,
+    focus_right_side: bool,
+    area: Rect,
+) where
+    T: InnerFieldMapping<F> + InnerTipLabel + InnerState,
+    F: InnerField,
+{
+    render_form(&mut DerivedFields::new(inner), f, focus_right_side, area);
+}
+/// Renders labels, the tip of the focused field, text inputs, radios and
+/// errors, with the directory browser over them when it is open.
+pub(super) fn render_form(
+    fields: &mut dyn FormFields,
+    f: &mut Frame,
+    focus_right_side: bool,
+    area: Rect,
+) {
+    let focus_index = fields.state_mut().focus_index.value;
+    // 表单布局 - 按宽度分为一或两列, 并滚动到焦点所在的行
+    let count = fields.count();
+    let field_areas = form_layout(f, area, count, focus_index, &mut fields.state_mut().scroll);
+    for (index, field_area) in field_areas.into_iter().enumerate() {
+        let Some(field_area) = field_area else {
+            continue;
+        };
+        let focused = focus_right_side && index == focus_index;
+        let label = fields.label(index);
+        f.render_widget(
+            Paragraph::new(label.clone()).centered().block(
+                Block::default()
+                    .borders(Borders::all())
+                    .border_type(BorderType::Thick),
+            ),
+            field_area.label,
+        );
+        let focus_block = Block::new()
+            .borders(Borders::ALL)
+            .border_style(if focused {
+                Color::Red
+            } else {
+                Color::default()
+            })
+            .border_type(BorderType::Thick);
+        if focused {
+            f.render_widget(
+                Paragraph::new(fields.tip(index))
+                    .style(Color::Blue)
+                    .centered(),
+                field_area.tip,
+            );
+        }
+        if let Some(r) = fields.radio_mut(index) {
+            f.render_widget(
+                Paragraph::new(
+                    (0 .. r.length())
+                        .map(|curr| r.get_symbol(curr))
+                        .collect::<Vec<String>>()
+                        .join("    "),
+                )
+                .centered()
+                .block(focus_block),
+                field_area.input,
+            );
+            let unmet_requirements = r.unmet_requirements();
+            if !unmet_requirements.is_empty() {
+                f.render_widget(
+                    Paragraph::new(unmet_requirements.join(" | ")).style(Color::DarkGray),
+                    field_area.error,
+                );
+            }
+            continue;
+        }
+        let field_string_value = fields.value(index);
+        f.render_widget(
+            Paragraph::new(if field_string_value.is_empty() {
+                format!("Please input {label}")
+            } else {
+                field_string_value.clone()
+            })
+            .centered()
+            .style(if field_string_value.is_empty() {
+                Color::Gray
+            } else {
+                Color::default()
+            })
+            .block(focus_block),
+            field_area.input,
+        );
+        if focused && let Some(value) = fields.text_mut(index).cloned() {
+            f.set_cursor_position(
+                fields.state_mut().inputs[index].cursor_position(&value, field_area.input),
+            );
+        }
+        let error_message = &fields.state_mut().error_messages[index];
+        if !error_message.is_empty() {
+            f.render_widget(
+                Paragraph::new(error_message.clone()).style(Color::Red),
+                field_area.error,
+            );
+        }
+    }
+    if let Some(browser) = &fields.state_mut().browser {
+        browser.render(f, area);
+    }
+}
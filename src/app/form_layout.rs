@@ -0,0 +1,112 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+};
+use ratatui_macros::constraints;
+/// Rows taken by a field: its tip, its bordered input and its error.
+const FIELD_HEIGHT: u16 = 5;
+/// Below this width the fields are laid out in a single column.
+const TWO_COLUMNS_WIDTH: u16 = 100;
+/// Where the parts of a field are drawn.
+pub(super) struct FieldArea {
+    pub(super) label: Rect,
+    pub(super) tip:   Rect,
+    pub(super) input: Rect,
+    pub(super) error: Rect,
+}
+/// Places `count` fields in `area`, in one or two columns depending on its
+/// width, scrolled so that the `focus`ed field is visible. Fields out of view
+/// get `None`, and a scrollbar is drawn when some are.
+pub(super) fn form_layout(
+    f: &mut Frame,
+    area: Rect,
+    count: usize,
+    focus: usize,
+    scroll: &mut usize,
+) -> Vec<Option<FieldArea>> {
+    let columns = if area.width >= TWO_COLUMNS_WIDTH {
+        2
+    } else {
+        1
+    };
+    let rows = count.div_ceil(columns);
+    let visible = usize::from(area.height / FIELD_HEIGHT).max(1);
+    // 只在焦点离开可见区域时滚动, 避免每次切换焦点画面都跳动
+    let focus_row = focus / columns;
+    if focus_row < *scroll {
+        *scroll = focus_row;
+    } else if focus_row >= *scroll + visible {
+        *scroll = focus_row + 1 - visible;
+    }
+    *scroll = (*scroll).min(rows.saturating_sub(visible));
+    let mut form_area = area;
+    if rows > visible {
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            area,
+            &mut ScrollbarState::new(rows.saturating_sub(visible) + 1)
+                .position(*scroll)
+                .viewport_content_length(visible),
+        );
+        form_area.width = form_area.width.saturating_sub(1);
+    }
+    let row_areas =
+        Layout::vertical((0 .. visible).map(|_| Constraint::Length(FIELD_HEIGHT))).split(form_area);
+    let label_width = (form_area.width / u16::try_from(columns).unwrap_or(1) / 3).min(20);
+    let split_label_input_layout = Layout::horizontal(constraints![==label_width,>=0]);
+    let split_tip_input_error_layout = Layout::vertical(constraints![<=1,<=3,<=1]);
+    (0 .. count)
+        .map(|index| {
+            let row = (index / columns).checked_sub(*scroll)?;
+            let row_area = *row_areas.get(row)?;
+            let column_area = Layout::horizontal((0 .. columns).map(|_| Constraint::Fill(1)))
+                .split(row_area)[index % columns];
+            let label_input_area = split_label_input_layout.split(column_area);
+            let input_areas = split_tip_input_error_layout.split(label_input_area[1]);
+            Some(FieldArea {
+                label: split_tip_input_error_layout.split(label_input_area[0])[1],
+                tip:   input_areas[0],
+                input: input_areas[1],
+                error: input_areas[2],
+            })
+        })
+        .collect()
+}
+#[cfg(test)]
+mod form_layout_test {
+    use super::form_layout;
+    use ratatui::{Terminal, backend::TestBackend};
+    #[test]
+    fn test_form_layout_scrolls_to_focus() {
+        let mut terminal = Terminal::new(TestBackend::new(60, 24)).unwrap();
+        let mut scroll = 0;
+        terminal
+            .draw(|f| {
+                let areas = form_layout(f, f.area(), 11, 10, &mut scroll);
+                assert!(areas[0].is_none());
+                assert!(areas[10].is_some());
+            })
+            .unwrap();
+        assert_eq!(scroll, 7);
+        terminal
+            .draw(|f| {
+                let areas = form_layout(f, f.area(), 11, 8, &mut scroll);
+                assert!(areas[7].is_some() && areas[8].is_some());
+            })
+            .unwrap();
+        assert_eq!(scroll, 7);
+        let mut terminal = Terminal::new(TestBackend::new(140, 40)).unwrap();
+        let mut scroll = 0;
+        terminal
+            .draw(|f| {
+                let areas = form_layout(f, f.area(), 11, 0, &mut scroll);
+                assert!(areas.iter().all(Option::is_some));
+                assert_eq!(
+                    areas[0].as_ref().unwrap().input.y,
+                    areas[1].as_ref().unwrap().input.y
+                );
+            })
+            .unwrap();
+    }
+}
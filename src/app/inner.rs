@@ -1,18 +1,30 @@
-use super::RadioOptionTrait;
-use crate::common::LoopNumber;
-use anyhow::Result;
+use super::{DirBrowser, DirBrowserOutput, RadioOptionTrait, TextInput};
+use crate::{
+    AppContext,
+    common::{Editor, LoopNumber, complete_directory, expand_path},
+    generate::Options,
+};
 use num_traits::{FromPrimitive, ToPrimitive};
 use ratatui::{
     Frame,
-    crossterm::event::{KeyCode, KeyEvent},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
     layout::Rect,
 };
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    mem,
+    path::PathBuf,
+    sync::Arc,
+};
 use strum::IntoEnumIterator;
 #[derive(Default)]
 pub(crate) struct InnerHandleKeyEventOutput {
     pub(crate) esc_handled: bool,
     pub(crate) exit:        bool,
+    /// The key was typed into a text field or taken by the browser, it has
+    /// no other meaning.
+    pub(crate) key_handled: bool,
 }
 impl InnerHandleKeyEventOutput {
     pub(super) fn with_exited(mut self) -> Self {
@@ -20,16 +32,25 @@ impl InnerHandleKeyEventOutput {
         self
     }
 
-    #[allow(dead_code)]
     pub(super) fn with_esc_handled(mut self) -> Self {
         self.esc_handled = true;
         self
     }
+
+    pub(super) fn with_key_handled(mut self) -> Self {
+        self.key_handled = true;
+        self
+    }
 }
 pub(super) trait InnerField:
     Clone + Copy + Display + IntoEnumIterator + FromPrimitive + ToPrimitive
 {
-    fn validate_string(self, value: &mut str) -> String;
+    fn validate_string(self, value: &mut str, context: &AppContext) -> String;
+    /// Whether the field is a directory, completed with Tab and browsed with
+    /// Ctrl-O.
+    fn is_path(self) -> bool {
+        false
+    }
 }
 pub(super) trait InnerFieldMapping<F: InnerField> {
     fn get_focus_field_mut(&mut self, field: F) -> Option<&mut String>;
@@ -44,72 +65,298 @@ pub(super) trait Inner: Sync {
     fn render(&mut self, f: &mut Frame, focus_right_side: bool, area: Rect);
     fn bottom_help_message(&self) -> String;
     fn handle_keyevent(&mut self, key: KeyEvent) -> InnerHandleKeyEventOutput;
-    fn create_and_edit(&self) -> Result<()>;
+    /// Pastes into the focused text field, if any.
+    fn handle_paste(&mut self, _text: &str) {}
+    /// The label and value of every field, as the form shows them.
+    fn summary(&mut self) -> Vec<(String, String)>;
+    /// Fills the fields with the values of a previous `summary`, by label.
+    fn restore(&mut self, _values: &[(String, String)]) {}
+    /// What to generate and where, `None` if nothing can be created.
+    fn generation(&self) -> Option<(Options, PathBuf)>;
+    /// The editor to open the created project with.
+    fn editor(&self) -> Editor;
 }
 #[derive(Clone)]
 pub(super) struct InnerCommonState {
     pub(super) focus_index:    LoopNumber,
     pub(super) error_messages: Vec<String>,
+    /// The first row of fields shown, the form scrolls when they do not all
+    /// fit.
+    pub(super) scroll:         usize,
+    /// The editing state of every field, used by text fields only.
+    pub(super) inputs:         Vec<TextInput>,
+    /// Opened from a path field.
+    pub(super) browser:        Option<DirBrowser>,
+    pub(super) context:        Arc<AppContext>,
 }
 impl InnerCommonState {
-    pub(super) fn new<F>() -> Self
+    pub(super) fn new<F>(context: &Arc<AppContext>) -> Self
     where
         F: InnerField,
     {
+        Self::with_count(F::iter().count(), context)
+    }
+
+    /// The state of a form of `count` fields, for forms only known at runtime.
+    pub(super) fn with_count(count: usize, context: &Arc<AppContext>) -> Self {
         Self {
-            focus_index:    LoopNumber::new(F::iter().count()),
-            error_messages: F::iter().map(|_| String::new()).collect(),
+            focus_index:    LoopNumber::new(count),
+            error_messages: vec![String::new(); count],
+            scroll:         0,
+            inputs:         vec![TextInput::default(); count],
+            browser:        None,
+            context:        context.clone(),
         }
     }
 }
 pub(super) trait InnerState {
-    fn state(self) -> InnerCommonState;
-    fn with_state(&mut self, state: InnerCommonState) -> &mut Self;
+    fn state_mut(&mut self) -> &mut InnerCommonState;
+}
+/// The fields of a form, by index, whether they are those of an inner deriving
+/// `InnerForm` or ones only known at runtime.
+pub(super) trait FormFields {
+    fn count(&self) -> usize;
+    fn label(&self, index: usize) -> String;
+    fn tip(&self, index: usize) -> String;
+    /// The value as it is shown, for fields which are not radios.
+    fn value(&self, index: usize) -> String;
+    fn text_mut(&mut self, index: usize) -> Option<&mut String>;
+    fn radio_mut(&mut self, index: usize) -> Option<&mut dyn RadioOptionTrait>;
+    /// An error message unless the text of the field is valid.
+    fn validate(&mut self, index: usize) -> String;
+    fn is_path(&self, index: usize) -> bool;
+    fn state_mut(&mut self) -> &mut InnerCommonState;
+}
+/// The fields of an inner deriving `InnerForm`, `F` being its field enum.
+pub(super) struct DerivedFields<'a, T, F> {
+    inner: &'a mut T,
+    field: PhantomData<F>,
+}
+impl<'a, T, F> DerivedFields<'a, T, F> {
+    pub(super) fn new(inner: &'a mut T) -> Self {
+        Self {
+            inner,
+            field: PhantomData,
+        }
+    }
+}
+impl<T, F> FormFields for DerivedFields<'_, T, F>
+where
+    T: InnerFieldMapping<F> + InnerTipLabel + InnerState,
+    F: InnerField,
+{
+    fn count(&self) -> usize {
+        T::labels().len()
+    }
+
+    fn label(&self, index: usize) -> String {
+        T::labels()[index].to_string()
+    }
+
+    fn tip(&self, index: usize) -> String {
+        T::tips()[index].to_string()
+    }
+
+    fn value(&self, index: usize) -> String {
+        format!("{:?}", self.inner.get_field(F::from_usize(index).unwrap())).replace('"', "")
+    }
+
+    fn text_mut(&mut self, index: usize) -> Option<&mut String> {
+        self.inner.get_focus_field_mut(F::from_usize(index)?)
+    }
+
+    fn radio_mut(&mut self, index: usize) -> Option<&mut dyn RadioOptionTrait> {
+        self.inner.get_radio(F::from_usize(index)?)
+    }
+
+    fn validate(&mut self, index: usize) -> String {
+        let context = self.inner.state_mut().context.clone();
+        let Some(field) = F::from_usize(index) else {
+            return String::new();
+        };
+        self.inner
+            .get_focus_field_mut(field)
+            .map(|x| field.validate_string(x, &context))
+            .unwrap_or_default()
+    }
+
+    fn is_path(&self, index: usize) -> bool {
+        F::from_usize(index).is_some_and(InnerField::is_path)
+    }
+
+    fn state_mut(&mut self) -> &mut InnerCommonState {
+        self.inner.state_mut()
+    }
+}
+pub(super) fn inner_summary<T, F>(inner: &mut T) -> Vec<(String, String)>
+where
+    T: InnerFieldMapping<F> + InnerTipLabel + InnerState,
+    F: InnerField,
+{
+    form_summary(&mut DerivedFields::new(inner))
+}
+/// The label and value of every field, as the form shows them.
+pub(super) fn form_summary(fields: &mut dyn FormFields) -> Vec<(String, String)> {
+    (0 .. fields.count())
+        .map(|index| {
+            (
+                fields.label(index),
+                match fields.radio_mut(index) {
+                    Some(radio) => radio.current(),
+                    None => fields.value(index),
+                },
+            )
+        })
+        .collect()
+}
+pub(super) fn inner_restore<T, F>(inner: &mut T, values: &[(String, String)])
+where
+    T: InnerFieldMapping<F> + InnerTipLabel + InnerState,
+    F: InnerField,
+{
+    form_restore(&mut DerivedFields::new(inner), values);
+}
+/// Sets every field whose label is in `values` to its value, radios keep
+/// their selection when the value is not one of their options anymore.
+pub(super) fn form_restore(fields: &mut dyn FormFields, values: &[(String, String)]) {
+    for index in 0 .. fields.count() {
+        let label = fields.label(index);
+        let Some((_, value)) = values.iter().find(|(other, _)| *other == label) else {
+            continue;
+        };
+        if let Some(radio) = fields.radio_mut(index) {
+            // 转一整圈没找到时回到原来的选项
+            for _ in 0 .. radio.length() {
+                if radio.current() == *value {
+                    break;
+                }
+                radio.next();
+            }
+        } else if edit_text(fields, index, |input, x| input.set(x, value)).is_some() {
+            validate_field(fields, index);
+        }
+    }
+}
+/// Edits the text of the field at `index` with its `TextInput`, `None` if it
+/// has no text.
+fn edit_text<R>(
+    fields: &mut dyn FormFields,
+    index: usize,
+    edit: impl FnOnce(&mut TextInput, &mut String) -> R,
+) -> Option<R> {
+    // 文本和它的编辑状态都属于 fields, 先把编辑状态取出来
+    let mut input = mem::take(&mut fields.state_mut().inputs[index]);
+    let result = fields.text_mut(index).map(|x| edit(&mut input, x));
+    fields.state_mut().inputs[index] = input;
+    result
+}
+fn validate_field(fields: &mut dyn FormFields, index: usize) {
+    let error = fields.validate(index);
+    fields.state_mut().error_messages[index] = error;
 }
 pub(super) fn handle_inner_keyevent<T, F>(inner: &mut T, key: KeyEvent) -> InnerHandleKeyEventOutput
 where
-    T: InnerFieldMapping<F> + Inner + InnerState + Clone,
+    T: InnerFieldMapping<F> + InnerTipLabel + InnerState,
     F: InnerField,
 {
-    let mut state = inner.clone().state();
-    let field = F::from_usize(state.focus_index.value).unwrap();
-    match key.code {
-        KeyCode::Char(c) => {
-            if let Some(x) = inner.get_focus_field_mut(field) {
-                x.push(c);
-                state.error_messages[state.focus_index.value] = field.validate_string(x);
+    handle_form_keyevent(&mut DerivedFields::new(inner), key)
+}
+/// Edits, browses, completes and moves between the fields of a form, exits
+/// once Enter is pressed with every field valid.
+pub(super) fn handle_form_keyevent(
+    fields: &mut dyn FormFields,
+    key: KeyEvent,
+) -> InnerHandleKeyEventOutput {
+    let index = fields.state_mut().focus_index.value;
+    if let Some(browser) = fields.state_mut().browser.as_mut() {
+        match browser.handle_keyevent(key) {
+            Some(DirBrowserOutput::Chosen(dir)) => {
+                fields.state_mut().browser = None;
+                if edit_text(fields, index, |input, x| {
+                    input.set(x, &dir.display().to_string());
+                })
+                .is_some()
+                {
+                    validate_field(fields, index);
+                }
             }
+            Some(DirBrowserOutput::Cancel) => fields.state_mut().browser = None,
+            None => {}
         }
-        KeyCode::Backspace => {
-            if let Some(x) = inner.get_focus_field_mut(field) {
-                x.pop();
-                state.error_messages[state.focus_index.value] = field.validate_string(x);
+        return InnerHandleKeyEventOutput::default()
+            .with_esc_handled()
+            .with_key_handled();
+    }
+    if fields.is_path(index)
+        && let Some(x) = fields.text_mut(index)
+    {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('o') if ctrl => {
+                let start = expand_path(x);
+                fields.state_mut().browser = Some(DirBrowser::new(start));
+                return InnerHandleKeyEventOutput::default();
             }
+            // 没有可补全的内容时, Tab 照常切换焦点
+            KeyCode::Tab => {
+                if let Some(completed) = complete_directory(x) {
+                    edit_text(fields, index, |input, x| input.set(x, &completed));
+                    validate_field(fields, index);
+                    return InnerHandleKeyEventOutput::default();
+                }
+            }
+            _ => {}
         }
+    }
+    if edit_text(fields, index, |input, x| input.handle_keyevent(x, key)) == Some(true) {
+        validate_field(fields, index);
+        return InnerHandleKeyEventOutput::default().with_key_handled();
+    }
+    match key.code {
         KeyCode::Enter => {
-            F::iter().for_each(|field| {
-                if let Some(x) = inner.get_focus_field_mut(field) {
-                    state.error_messages[field.to_usize().unwrap()] = field.validate_string(x);
+            for index in 0 .. fields.count() {
+                if fields.text_mut(index).is_some() {
+                    validate_field(fields, index);
                 }
-            });
-            if state.error_messages.iter().all(String::is_empty) {
+            }
+            if fields
+                .state_mut()
+                .error_messages
+                .iter()
+                .all(String::is_empty)
+            {
                 return InnerHandleKeyEventOutput::default().with_exited();
             }
         }
         KeyCode::Tab => {
+            let state = fields.state_mut();
             state.focus_index = state.focus_index.next();
         }
         KeyCode::BackTab => {
+            let state = fields.state_mut();
             state.focus_index = state.focus_index.prev();
         }
         KeyCode::Left => {
-            inner.get_radio(field).map(RadioOptionTrait::prev);
+            fields.radio_mut(index).map(RadioOptionTrait::prev);
         }
         KeyCode::Right => {
-            inner.get_radio(field).map(RadioOptionTrait::next);
+            fields.radio_mut(index).map(RadioOptionTrait::next);
         }
         _ => {}
     }
-    inner.with_state(state);
     InnerHandleKeyEventOutput::default()
 }
+pub(super) fn handle_inner_paste<T, F>(inner: &mut T, text: &str)
+where
+    T: InnerFieldMapping<F> + InnerTipLabel + InnerState,
+    F: InnerField,
+{
+    handle_form_paste(&mut DerivedFields::new(inner), text);
+}
+/// Pastes `text` into the focused field, if it has a text.
+pub(super) fn handle_form_paste(fields: &mut dyn FormFields, text: &str) {
+    let index = fields.state_mut().focus_index.value;
+    if edit_text(fields, index, |input, x| input.paste(x, text)).is_some() {
+        validate_field(fields, index);
+    }
+}
@@ -1,6 +1,9 @@
-use super::SpringBootInner;
-use crate::common::Executable;
-use anyhow::{Error, Result};
+use super::{CmakeInner, SpringBootInner, TemplateInner};
+use crate::{
+    AppContext,
+    common::{Executable, ProjectType},
+};
+use anyhow::{Error, Result, anyhow};
 use num_traits::ToPrimitive;
 use ratatui::{
     Terminal,
@@ -11,137 +14,377 @@ use ratatui::{
     widgets::{Block, LineGauge, Paragraph},
 };
 use ratatui_macros::constraints;
-use tokio::sync::mpsc;
+use std::{any::TypeId, pin::Pin, sync::Arc, time::Duration};
+use strum::IntoEnumIterator;
+use tokio::{sync::mpsc, task::JoinSet};
+/// How long the prepare screen waits for a key before it is redrawn.
+const PREPARE_TICK: Duration = Duration::from_millis(50);
+/// `steps` steps of the stage `stage` are done.
 #[derive(Clone, Copy)]
+struct PrepareStep {
+    stage: usize,
+    steps: usize,
+}
+/// What the stages of a registry report while they are prepared.
+enum PrepareEvent {
+    Step(PrepareStep),
+    Error(Error),
+    /// Sent last, with the stages of the wave which failed, empty when every
+    /// wave is prepared. The waves after a failed one are not run.
+    Finished(Vec<usize>),
+}
+/// Handed to a prepare stage to report its steps.
 pub(crate) struct PrepareRecv {
-    step:   usize,
-    offset: f64,
+    stage: usize,
+    steps: usize,
+    len:   usize,
+    tx:    mpsc::UnboundedSender<PrepareEvent>,
 }
 impl PrepareRecv {
-    pub(crate) fn new(offset: f64) -> Self {
-        Self {
-            step: usize::from(offset != 0.),
-            offset,
-        }
+    pub(crate) fn send_ok(&mut self) {
+        self.steps = (self.steps + 1).min(self.len);
+        self.send(None);
+    }
+
+    pub(crate) fn send_error(&mut self, error: Error) {
+        self.send(Some(error));
+    }
+
+    fn send(&self, error: Option<Error>) {
+        // The receiver is only gone once nobody waits for the progress anymore.
+        let _ = self.tx.send(match error {
+            Some(error) => PrepareEvent::Error(error),
+            None => PrepareEvent::Step(PrepareStep {
+                stage: self.stage,
+                steps: self.steps,
+            }),
+        });
+    }
+}
+pub(crate) trait PrepareTrait: 'static {
+    /// What this stage prepares, shown above its steps.
+    fn header() -> &'static str;
+    fn prepare(context: Arc<AppContext>, recv: PrepareRecv) -> impl Future<Output = bool> + Send;
+    fn descs() -> Vec<String>;
+}
+type PrepareFuture = Pin<Box<dyn Future<Output = bool> + Send>>;
+type PrepareFn = fn(Arc<AppContext>, PrepareRecv) -> PrepareFuture;
+fn boxed_prepare<P: PrepareTrait>(context: Arc<AppContext>, recv: PrepareRecv) -> PrepareFuture {
+    Box::pin(P::prepare(context, recv))
+}
+struct PrepareStage {
+    id:      TypeId,
+    header:  &'static str,
+    descs:   Vec<String>,
+    after:   Vec<TypeId>,
+    prepare: PrepareFn,
+}
+/// Every prepare stage, together with the stages it has to wait for.
+pub(crate) struct PrepareRegistry {
+    stages: Vec<PrepareStage>,
+}
+impl PrepareRegistry {
+    /// Stages every project type relies on, prepared before anything is shown.
+    pub(crate) fn environment() -> Self {
+        let mut registry = Self { stages: vec![] };
+        registry.register::<Executable>();
+        registry
     }
 
-    fn step(&self) -> f64 {
-        (self.step + 1).to_f64().unwrap()
+    /// Stages only `project_type` relies on, prepared in the background once
+    /// the environment is known.
+    pub(crate) fn project_type(project_type: ProjectType) -> Self {
+        let mut registry = Self { stages: vec![] };
+        match project_type {
+            ProjectType::SpringBoot => registry.register::<SpringBootInner>(),
+            ProjectType::Cmake => registry.register::<CmakeInner>(),
+            ProjectType::Template => registry.register::<TemplateInner>(),
+            ProjectType::Maven | ProjectType::Cargo | ProjectType::Import => &mut registry,
+        };
+        registry
     }
 
-    fn len(&self) -> usize {
-        PrepareTexts::descs()[self.offset.trunc().to_usize().unwrap()].len()
+    /// The environment followed by the stages of every project type.
+    fn all() -> Self {
+        let mut registry = Self::environment();
+        for project_type in ProjectType::iter() {
+            for mut stage in Self::project_type(project_type).stages {
+                stage.after.push(TypeId::of::<Executable>());
+                registry.stages.push(stage);
+            }
+        }
+        registry
     }
 
-    fn next_step(&mut self) -> &mut Self {
-        self.step += usize::from(self.step != self.len());
+    pub(crate) fn register<P: PrepareTrait>(&mut self) -> &mut Self {
+        self.stages.push(PrepareStage {
+            id:      TypeId::of::<P>(),
+            header:  P::header(),
+            descs:   P::descs(),
+            after:   vec![],
+            prepare: boxed_prepare::<P>,
+        });
         self
     }
 
-    pub(crate) fn send_done(&mut self, permit: &mut PreparePermit<'_>) {
-        self.step = self.len();
-        self.send(permit, None);
+    /// Groups the stages into waves, every stage of a wave only waits for the
+    /// stages of the previous waves and runs in parallel with its wave.
+    fn waves(&self) -> Vec<Vec<usize>> {
+        let mut levels: Vec<usize> = vec![];
+        for stage in &self.stages {
+            let level = self
+                .stages
+                .iter()
+                .zip(&levels)
+                .filter(|(other, _)| stage.after.contains(&other.id))
+                .map(|(_, level)| level + 1)
+                .max()
+                .unwrap_or_default();
+            levels.push(level);
+        }
+        let mut waves = vec![vec![]; levels.iter().max().map_or(0, |max| max + 1)];
+        for (index, level) in levels.into_iter().enumerate() {
+            waves[level].push(index);
+        }
+        waves
     }
 
-    pub(crate) fn send_ok(&mut self, permit: &mut PreparePermit<'_>) {
-        self.send(permit, None);
+    /// Why `stage` failed, when it did not report it itself.
+    fn failure(&self, stage: usize) -> Error {
+        anyhow!("{} failed", self.stages[stage].header)
     }
 
-    pub(crate) fn send_error(&mut self, permit: &mut PreparePermit<'_>, error: Error) {
-        self.send(permit, Some(error));
+    fn total_steps(&self) -> usize {
+        self.stages.iter().map(|stage| stage.descs.len()).sum()
     }
 
-    fn send(&mut self, permit: &mut PreparePermit<'_>, error: Option<Error>) {
-        if let Some(error) = error {
-            permit.next().unwrap().send(Err(error));
-        } else {
-            permit.next().unwrap().send(Ok(*self));
-            self.next_step();
+    async fn prepare(
+        context: Arc<AppContext>,
+        waves: Vec<Vec<(usize, usize, PrepareFn)>>,
+        tx: mpsc::UnboundedSender<PrepareEvent>,
+    ) {
+        let mut failed = vec![];
+        for wave in waves {
+            let mut stages = JoinSet::new();
+            for (stage, len, prepare) in wave {
+                let prepare = prepare(
+                    context.clone(),
+                    PrepareRecv {
+                        stage,
+                        steps: 0,
+                        len,
+                        tx: tx.clone(),
+                    },
+                );
+                stages.spawn(async move { (stage, prepare.await) });
+            }
+            failed = stages
+                .join_all()
+                .await
+                .into_iter()
+                .filter(|(_, prepared)| !prepared)
+                .map(|(stage, _)| stage)
+                .collect();
+            if !failed.is_empty() {
+                failed.sort_unstable();
+                break;
+            }
         }
+        let _ = tx.send(PrepareEvent::Finished(failed));
+    }
+
+    fn spawn(&self, context: Arc<AppContext>) -> mpsc::UnboundedReceiver<PrepareEvent> {
+        self.spawn_waves(context, self.waves())
+    }
+
+    fn spawn_waves(
+        &self,
+        context: Arc<AppContext>,
+        waves: Vec<Vec<usize>>,
+    ) -> mpsc::UnboundedReceiver<PrepareEvent> {
+        let waves = waves
+            .into_iter()
+            .map(|wave| {
+                wave.into_iter()
+                    .map(|index| {
+                        let stage = &self.stages[index];
+                        (index, stage.descs.len(), stage.prepare)
+                    })
+                    .collect()
+            })
+            .collect();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(Self::prepare(context, waves, tx));
+        rx
     }
 }
-pub(crate) trait PrepareTrait {
-    fn prepare(permit: &mut PreparePermit<'_>, offset: f64) -> impl Future<Output = bool> + Send;
-    fn descs() -> Vec<String>;
-}
-pub(crate) type PreparePermit<'a> = mpsc::PermitIterator<'a, Result<PrepareRecv>>;
-#[derive(Default)]
-struct PrepareProgress {
-    progress: f64,
+/// The progress of the stages of a registry, fed by their `PrepareRecv`s.
+pub(super) struct PrepareProgress {
+    registry: PrepareRegistry,
+    rx:       Option<mpsc::UnboundedReceiver<PrepareEvent>>,
+    steps:    Vec<usize>,
+    finished: bool,
+    /// The stages of the wave which failed, run again on retry.
+    failed:   Vec<usize>,
+    current:  usize,
     err_msg:  String,
 }
 impl PrepareProgress {
-    fn recv(&mut self, recv: Result<PrepareRecv>) -> &mut Self {
-        match recv {
-            Ok(recv) => {
-                self.progress = (recv.step() / PrepareTexts::f64_descs_len(self.header_offset())
-                    + recv.offset)
-                    / PrepareTexts::f64_headers_len();
-                self
-            }
-            Err(error) => {
-                self.err_msg = error.to_string();
-                println!("{error}");
-                self
-            }
+    fn new(registry: PrepareRegistry) -> Self {
+        Self {
+            steps: vec![0; registry.stages.len()],
+            registry,
+            rx: None,
+            finished: false,
+            failed: vec![],
+            current: 0,
+            err_msg: String::new(),
         }
     }
 
-    fn desc(&self) -> String {
-        let index = self.remove_header_offset()
-            * PrepareTexts::f64_descs_len(self.header_offset())
-            * PrepareTexts::f64_headers_len();
-        PrepareTexts::descs()[self.header_offset()][if index == 0. {
-            0
-        } else {
-            index.to_usize().unwrap() - 1
-        }]
-        .clone()
+    /// Starts preparing `project_type` in the background.
+    pub(super) fn project_type(context: Arc<AppContext>, project_type: ProjectType) -> Self {
+        let mut progress = Self::new(PrepareRegistry::project_type(project_type));
+        progress.spawn(context);
+        progress
     }
 
-    fn preparing(&self) -> bool {
-        (self.progress - 1.).abs() != 0.
+    fn spawn(&mut self, context: Arc<AppContext>) {
+        self.rx = Some(self.registry.spawn(context));
     }
 
-    fn remove_header_offset(&self) -> f64 {
-        (self.progress * PrepareTexts::f64_headers_len()).fract() / PrepareTexts::f64_headers_len()
+    /// Runs the failed wave again, then the waves after it, once the
+    /// preparation is over. Returns whether anything is run again.
+    fn retry(&mut self, context: Arc<AppContext>) -> bool {
+        if !self.finished || self.failed.is_empty() {
+            return false;
+        }
+        let waves = self.registry.waves();
+        let after = waves
+            .iter()
+            .position(|wave| wave.contains(&self.failed[0]))
+            .map_or(waves.len(), |position| position + 1);
+        let failed = std::mem::take(&mut self.failed);
+        for &stage in &failed {
+            self.steps[stage] = 0;
+        }
+        self.finished = false;
+        self.err_msg.clear();
+        self.rx = Some(
+            self.registry.spawn_waves(
+                context,
+                [failed]
+                    .into_iter()
+                    .chain(waves.into_iter().skip(after))
+                    .collect(),
+            ),
+        );
+        true
     }
 
-    fn header_offset(&self) -> usize {
-        (self.progress * PrepareTexts::f64_headers_len())
-            .trunc()
-            .to_usize()
-            .unwrap()
+    /// Takes in every step reported since the last update.
+    pub(super) fn update(&mut self) {
+        while let Some(recv) = self.rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+            self.recv(recv);
+        }
     }
 
-    fn header_text(&self) -> &'static str {
-        let header_index = (self.progress * PrepareTexts::f64_headers_len())
-            .trunc()
-            .to_usize()
-            .unwrap();
-        PrepareTexts::headers()[header_index]
+    fn recv(&mut self, recv: PrepareEvent) -> &mut Self {
+        match recv {
+            PrepareEvent::Step(step) => {
+                self.steps[step.stage] = step.steps;
+                self.current = step.stage;
+            }
+            // 只显示第一个错误
+            PrepareEvent::Error(error) => {
+                if self.err_msg.is_empty() {
+                    self.err_msg = error.to_string();
+                }
+            }
+            PrepareEvent::Finished(failed) => {
+                self.finished = true;
+                if let Some(&stage) = failed.first() {
+                    self.current = stage;
+                    if self.err_msg.is_empty() {
+                        self.err_msg = self.registry.failure(stage).to_string();
+                    }
+                }
+                self.failed = failed;
+            }
+        }
+        self
     }
 
-    fn percent(&mut self) -> String {
-        format!("{:.2}%", self.progress * 100.)
+    /// Every step is done without any error.
+    pub(super) fn prepared(&self) -> bool {
+        !self.preparing() && self.err_msg.is_empty()
     }
-}
-struct PrepareTexts {}
-impl PrepareTexts {
-    fn headers() -> &'static [&'static str] {
-        &["Environment checking", "Inner preparing"]
+
+    fn done_steps(&self) -> usize {
+        self.steps.iter().sum()
+    }
+
+    fn ratio(&self) -> f64 {
+        let total = self.registry.total_steps();
+        if total == 0 {
+            return 1.;
+        }
+        self.done_steps().to_f64().unwrap() / total.to_f64().unwrap()
+    }
+
+    fn desc(&self) -> String {
+        if !self.err_msg.is_empty() {
+            return self.err_msg.clone();
+        }
+        let Some(stage) = self.registry.stages.get(self.current) else {
+            return String::new();
+        };
+        stage
+            .descs
+            .get(self.steps[self.current].min(stage.descs.len().saturating_sub(1)))
+            .cloned()
+            .unwrap_or_default()
     }
 
-    fn descs() -> Vec<Vec<String>> {
-        vec![Executable::descs(), SpringBootInner::descs()]
+    fn preparing(&self) -> bool {
+        !self.finished && self.done_steps() < self.registry.total_steps()
     }
 
-    fn f64_headers_len() -> f64 {
-        Self::headers().len().to_f64().unwrap()
+    fn header_text(&self) -> &'static str {
+        self.registry
+            .stages
+            .get(self.current)
+            .map_or("", |stage| stage.header)
     }
 
-    fn f64_descs_len(offset: usize) -> f64 {
-        Self::descs()[offset].len().to_f64().unwrap()
+    fn percent(&self) -> String {
+        format!("{:.2}%", self.ratio() * 100.)
+    }
+
+    /// Renders the header, the current step and a gauge, one line each.
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect) {
+        let split_area = Layout::vertical(constraints![==1/3;3]).split(area);
+        frame.render_widget(Paragraph::new(self.header_text()).centered(), split_area[0]);
+        frame.render_widget(
+            Paragraph::new(self.desc())
+                .centered()
+                .style(if self.err_msg.is_empty() {
+                    Style::default()
+                } else {
+                    Style::default().fg(Color::Red)
+                }),
+            split_area[1],
+        );
+        frame.render_widget(
+            LineGauge::default()
+                .filled_symbol("⣿")
+                .unfilled_symbol("⣿")
+                .filled_style(Style::default().fg(tailwind::CYAN.c400))
+                .unfilled_style(Style::default().fg(tailwind::CYAN.c800))
+                .label(self.percent())
+                .ratio(self.ratio()),
+            split_area[2],
+        );
     }
 }
 #[derive(Default, PartialEq)]
@@ -160,12 +403,44 @@ impl PrepareStatus {
         self == &Self::Stop
     }
 }
-#[derive(Default)]
 pub struct PrepareApplication {
+    context:  Arc<AppContext>,
     progress: PrepareProgress,
     status:   PrepareStatus,
 }
 impl PrepareApplication {
+    #[must_use]
+    pub fn new(context: Arc<AppContext>) -> Self {
+        Self {
+            context,
+            progress: PrepareProgress::new(PrepareRegistry::environment()),
+            status: PrepareStatus::default(),
+        }
+    }
+
+    /// Runs every prepare step without drawing anything, returning the errors
+    /// of the failed steps.
+    pub(crate) async fn prepare_headless(context: Arc<AppContext>) -> Vec<Error> {
+        let registry = PrepareRegistry::all();
+        let mut rx = registry.spawn(context);
+        let mut errors = vec![];
+        while let Some(recv) = rx.recv().await {
+            match recv {
+                PrepareEvent::Step(_) => {}
+                PrepareEvent::Error(error) => errors.push(error),
+                PrepareEvent::Finished(failed) => {
+                    if let Some(&stage) = failed.first()
+                        && errors.is_empty()
+                    {
+                        errors.push(registry.failure(stage));
+                    }
+                    break;
+                }
+            }
+        }
+        errors
+    }
+
     /// # Errors
     /// # Panics
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()>
@@ -174,21 +449,11 @@ impl PrepareApplication {
         <B as Backend>::Error: Send,
         <B as Backend>::Error: 'static,
     {
-        let buffer = PrepareTexts::descs().iter().map(Vec::len).sum();
-        let (tx, mut rx) = mpsc::channel(buffer);
-        tokio::spawn(async move {
-            let mut permit = tx.reserve_many(buffer).await.unwrap();
-            // Environment checking spawn
-            let preparing = Executable::prepare(&mut permit, 0.).await;
-            // Inner preparing spawn
-            if preparing {
-                SpringBootInner::prepare(&mut permit, 1.).await;
-            }
-        });
-        while self.status.running() && self.progress.preparing() {
+        self.progress.spawn(self.context.clone());
+        while self.status.running() && !self.progress.prepared() {
             terminal.draw(|f| self.ui(f))?;
             self.handle_event()?;
-            self.update(&mut rx);
+            self.update();
         }
         Ok(())
     }
@@ -196,45 +461,32 @@ impl PrepareApplication {
     fn ui(&mut self, frame: &mut Frame) {
         let prepare_h_area = Layout::horizontal(constraints![*=1,==25%,*=1]).split(frame.area())[1];
         let prepare_area = Layout::vertical(constraints![*=1,==20%,*=1]).split(prepare_h_area)[1];
-        frame.render_widget(
-            Paragraph::default().centered().block(Block::bordered()),
-            prepare_area,
-        );
-        let prepare_split_area =
-            Layout::vertical(constraints![==1/3;3]).split(prepare_area.inner(Margin::new(1, 1)));
-        frame.render_widget(
-            Paragraph::new(self.progress.header_text()).centered(),
-            prepare_split_area[0],
-        );
-        frame.render_widget(
-            Paragraph::new(self.progress.desc()).centered(),
-            prepare_split_area[1],
-        );
-        frame.render_widget(
-            LineGauge::default()
-                .filled_symbol("⣿")
-                .unfilled_symbol("⣿")
-                .filled_style(Style::default().fg(tailwind::CYAN.c400))
-                .unfilled_style(Style::default().fg(tailwind::CYAN.c800))
-                .label(self.progress.percent())
-                .ratio(self.progress.progress),
-            prepare_split_area[2],
-        );
+        let mut block = Block::bordered();
+        if self.status.stopped() {
+            block = block.title_bottom(Line::from(" r: retry | q: quit ").centered());
+        }
+        frame.render_widget(Paragraph::default().centered().block(block), prepare_area);
+        self.progress
+            .render(frame, prepare_area.inner(Margin::new(1, 1)));
     }
 
+    /// Waits for a key until the next tick, so that the progress is redrawn
+    /// only as often as it can change.
     fn handle_event(&mut self) -> Result<()> {
+        if !event::poll(PREPARE_TICK)? {
+            return Ok(());
+        }
+        let event = event::read()?;
         if self.status.stopped()
-            && let Some(key) = event::read()?.as_key_press_event()
+            && let Some(key) = event.as_key_press_event()
             && let KeyCode::Char(c) = key.code
         {
             match c {
                 'q' => {
                     std::process::exit(0);
                 }
-                'r' => {
-                    // retry current failed prepare step. If the retry succeed, continue prepare
-                    // progress.
-                    todo!()
+                'r' if self.progress.retry(self.context.clone()) => {
+                    self.status = PrepareStatus::Start;
                 }
                 _ => {}
             }
@@ -242,13 +494,142 @@ impl PrepareApplication {
         Ok(())
     }
 
-    fn update(&mut self, rx: &mut mpsc::Receiver<Result<PrepareRecv>>) {
-        if self.progress.err_msg.is_empty() {
-            if let Ok(recv) = rx.try_recv() {
-                self.progress.recv(recv);
-            }
-        } else {
+    fn update(&mut self) {
+        self.progress.update();
+        if !self.progress.err_msg.is_empty() {
             self.status = PrepareStatus::Stop;
         }
     }
 }
+#[cfg(test)]
+mod prepare_test {
+    use super::{PrepareProgress, PrepareRecv, PrepareRegistry, PrepareTrait};
+    use crate::{AppContext, common::ProjectType};
+    use std::{
+        any::TypeId,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    };
+    static FLAKY_FAILED: AtomicBool = AtomicBool::new(false);
+    static SKIPPED_RAN: AtomicBool = AtomicBool::new(false);
+    struct Quick;
+    impl PrepareTrait for Quick {
+        fn header() -> &'static str {
+            "Quick"
+        }
+
+        async fn prepare(_: Arc<AppContext>, mut recv: PrepareRecv) -> bool {
+            recv.send_ok();
+            recv.send_ok();
+            true
+        }
+
+        fn descs() -> Vec<String> {
+            vec!["first".to_string(), "second".to_string()]
+        }
+    }
+    /// Fails the first time without reporting why.
+    struct Flaky;
+    impl PrepareTrait for Flaky {
+        fn header() -> &'static str {
+            "Flaky"
+        }
+
+        async fn prepare(_: Arc<AppContext>, mut recv: PrepareRecv) -> bool {
+            if FLAKY_FAILED.swap(true, Ordering::SeqCst) {
+                recv.send_ok();
+                return true;
+            }
+            false
+        }
+
+        fn descs() -> Vec<String> {
+            vec!["flaky".to_string()]
+        }
+    }
+    struct Skipped;
+    impl PrepareTrait for Skipped {
+        fn header() -> &'static str {
+            "Skipped"
+        }
+
+        async fn prepare(_: Arc<AppContext>, mut recv: PrepareRecv) -> bool {
+            SKIPPED_RAN.store(true, Ordering::SeqCst);
+            recv.send_ok();
+            true
+        }
+
+        fn descs() -> Vec<String> {
+            vec!["skipped".to_string()]
+        }
+    }
+    fn after<P: 'static>(registry: &mut PrepareRegistry) {
+        registry
+            .stages
+            .last_mut()
+            .unwrap()
+            .after
+            .push(TypeId::of::<P>());
+    }
+    #[test]
+    fn test_waves() {
+        let mut registry = PrepareRegistry { stages: vec![] };
+        registry.register::<Quick>();
+        registry.register::<Flaky>();
+        after::<Quick>(&mut registry);
+        registry.register::<Skipped>();
+        after::<Flaky>(&mut registry);
+        after::<Quick>(&mut registry);
+        assert_eq!(registry.waves(), [vec![0], vec![1], vec![2]]);
+        assert_eq!(registry.total_steps(), 4);
+        let all = PrepareRegistry::all();
+        assert_eq!(all.waves()[0], [0]);
+        assert!(all.waves().len() == 2 && all.waves()[1].len() == all.stages.len() - 1);
+    }
+    #[test]
+    fn test_project_type_stages() {
+        // 不需要准备的项目类型一开始就是准备好的
+        assert!(PrepareProgress::new(PrepareRegistry::project_type(ProjectType::Cargo)).prepared());
+        for project_type in [
+            ProjectType::Cmake,
+            ProjectType::SpringBoot,
+            ProjectType::Template,
+        ] {
+            let registry = PrepareRegistry::project_type(project_type);
+            assert_eq!(registry.stages.len(), 1);
+            assert!(!PrepareProgress::new(registry).prepared());
+        }
+    }
+    #[tokio::test]
+    async fn test_failed_wave_finishes() {
+        let mut registry = PrepareRegistry { stages: vec![] };
+        registry.register::<Quick>();
+        registry.register::<Flaky>();
+        registry.register::<Skipped>();
+        after::<Flaky>(&mut registry);
+        let context = Arc::new(AppContext::default());
+        let mut progress = PrepareProgress::new(registry);
+        assert!(!progress.retry(context.clone()));
+        progress.spawn(context.clone());
+        let mut rx = progress.rx.take().unwrap();
+        while let Some(event) = rx.recv().await {
+            progress.recv(event);
+        }
+        assert!(!SKIPPED_RAN.load(Ordering::SeqCst));
+        assert!(!progress.preparing());
+        assert!(!progress.prepared());
+        assert_eq!(progress.steps, [2, 0, 0]);
+        assert_eq!(progress.desc(), "Flaky failed");
+        // 重试只跑失败的那一波和之后的
+        assert!(progress.retry(context));
+        let mut rx = progress.rx.take().unwrap();
+        while let Some(event) = rx.recv().await {
+            progress.recv(event);
+        }
+        assert!(SKIPPED_RAN.load(Ordering::SeqCst));
+        assert!(progress.prepared());
+        assert_eq!(progress.steps, [2, 1, 1]);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::generate::Report;
+use anyhow::Result;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+/// Lists what creating the project would write and run, for `--dry-run`.
+pub(super) struct Preview {
+    lines:  Vec<Line<'static>>,
+    scroll: u16,
+}
+impl Preview {
+    pub(super) fn new(report: Result<Report>) -> Self {
+        let lines = match report {
+            Ok(report) => Self::report_lines(&report),
+            Err(error) => vec![Line::styled(error.to_string(), Color::Red)],
+        };
+        Self { lines, scroll: 0 }
+    }
+
+    fn report_lines(report: &Report) -> Vec<Line<'static>> {
+        let section = |title: &str| {
+            Line::styled(
+                title.to_string(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+        let mut lines = vec![section("Directories and files")];
+        for path in &report.created {
+            lines.push(Line::raw(format!(
+                "  {}{}",
+                path.display(),
+                if report.is_dir(path) { "/" } else { "" }
+            )));
+        }
+        lines.push(Line::default());
+        lines.push(section("Commands"));
+        if report.commands.is_empty() {
+            lines.push(Line::styled("  none", Color::DarkGray));
+        }
+        for command in &report.commands {
+            lines.push(Line::raw(format!("  {command}")));
+        }
+        for (path, contents) in &report.contents {
+            lines.push(Line::default());
+            lines.push(section(&path.display().to_string()));
+            for line in contents.lines() {
+                lines.push(Line::raw(format!("  {line}")));
+            }
+        }
+        lines
+    }
+
+    pub(super) fn scroll_down(&mut self) {
+        let max = u16::try_from(self.lines.len().saturating_sub(1)).unwrap_or(u16::MAX);
+        self.scroll = (self.scroll + 1).min(max);
+    }
+
+    pub(super) fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(self.lines.clone())
+                .scroll((self.scroll, 0))
+                .block(
+                    Block::default()
+                        .title("Dry run, nothing will be created")
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Yellow)),
+                ),
+            area,
+        );
+    }
+}
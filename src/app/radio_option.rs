@@ -1,64 +1,158 @@
-use crate::common::LoopNumber;
+use crate::{AppContext, common::LoopNumber};
 use std::fmt::Display;
 use strum::IntoEnumIterator;
 pub(crate) trait RadioOptionValue:
     Display + Default + Copy + IntoEnumIterator + PartialEq
 {
-    fn selectable(&self) -> bool;
+    fn selectable(&self, context: &AppContext) -> bool;
+    /// Why this value cannot be selected with the installed tools.
+    fn unmet_requirement(&self, _context: &AppContext) -> Option<String> {
+        None
+    }
 }
 #[derive(Clone)]
 pub(super) struct RadioOption<V>
 where
     V: RadioOptionValue,
 {
-    pub(super) value: V,
-    id:               LoopNumber,
+    pub(super) value:   V,
+    id:                 LoopNumber,
+    /// The selectable values, or every value if none is selectable, so that
+    /// the radio never ends up empty.
+    values:             Vec<V>,
+    unmet_requirements: Vec<String>,
 }
-impl<V: RadioOptionValue> Default for RadioOption<V> {
-    fn default() -> Self {
-        let value = V::default();
-        let mut id = LoopNumber::new(V::iter().filter(RadioOptionValue::selectable).count());
-        for v in V::iter().filter(RadioOptionValue::selectable) {
-            if v == value {
-                break;
-            }
-            id = id.next();
+impl<V: RadioOptionValue> RadioOption<V> {
+    pub(super) fn new(context: &AppContext) -> Self {
+        let mut values: Vec<V> = V::iter().filter(|v| v.selectable(context)).collect();
+        if values.is_empty() {
+            values = V::iter().collect();
+        }
+        // Fall back to the closest selectable value before the default one, so
+        // that e.g. an older cargo defaults to the newest edition it supports.
+        let default_rank = V::iter().position(|v| v == V::default());
+        let index = values
+            .iter()
+            .position(|v| *v == V::default())
+            .or_else(|| {
+                values
+                    .iter()
+                    .rposition(|v| V::iter().position(|x| x == *v) < default_rank)
+            })
+            .unwrap_or_default();
+        let mut id = LoopNumber::new(values.len());
+        id.value = index;
+        Self {
+            value: values[index],
+            id,
+            values,
+            unmet_requirements: V::iter()
+                .filter_map(|v| {
+                    v.unmet_requirement(context)
+                        .map(|reason| format!("{v}: {reason}"))
+                })
+                .collect(),
         }
-        Self { value, id }
     }
 }
 pub(super) trait RadioOptionTrait {
     fn next(&mut self);
     fn prev(&mut self);
     fn get_symbol(&self, curr: usize) -> String;
+    /// The selected value as it is shown.
+    fn current(&self) -> String;
     fn length(&self) -> usize;
+    fn unmet_requirements(&self) -> Vec<String>;
 }
 impl<V: RadioOptionValue> RadioOptionTrait for RadioOption<V> {
     fn next(&mut self) {
         self.id = self.id.next();
-        self.value = V::iter()
-            .filter(RadioOptionValue::selectable)
-            .collect::<Vec<V>>()[self.id.value];
+        self.value = self.values[self.id.value];
+    }
+
+    fn prev(&mut self) {
+        self.id = self.id.prev();
+        self.value = self.values[self.id.value];
+    }
+
+    fn get_symbol(&self, curr: usize) -> String {
+        format!(
+            "{} {}",
+            if self.id.value == curr { "◉" } else { "○" },
+            self.values[curr]
+        )
+    }
+
+    fn current(&self) -> String {
+        self.value.to_string()
+    }
+
+    fn length(&self) -> usize {
+        self.id.length
+    }
+
+    fn unmet_requirements(&self) -> Vec<String> {
+        self.unmet_requirements.clone()
+    }
+}
+/// A radio of values only known at runtime, like the options of a template
+/// prompt.
+#[derive(Clone)]
+pub(super) struct Choice {
+    id:                 LoopNumber,
+    values:             Vec<String>,
+    unmet_requirements: Vec<String>,
+}
+impl Choice {
+    /// Selects `default` if it is one of `values`, the first value otherwise.
+    pub(super) fn new(values: Vec<String>, default: Option<&str>) -> Self {
+        let mut id = LoopNumber::new(values.len());
+        id.value = default
+            .and_then(|default| values.iter().position(|value| value == default))
+            .unwrap_or_default();
+        Self {
+            id,
+            values,
+            unmet_requirements: vec![],
+        }
+    }
+
+    /// Shown below the radio, like the requirements unmet by a value.
+    pub(super) fn with_notes(mut self, notes: Vec<String>) -> Self {
+        self.unmet_requirements = notes;
+        self
+    }
+
+    pub(super) fn index(&self) -> usize {
+        self.id.value
+    }
+}
+impl RadioOptionTrait for Choice {
+    fn next(&mut self) {
+        self.id = self.id.next();
     }
 
     fn prev(&mut self) {
         self.id = self.id.prev();
-        self.value = V::iter()
-            .filter(RadioOptionValue::selectable)
-            .collect::<Vec<V>>()[self.id.value];
     }
 
     fn get_symbol(&self, curr: usize) -> String {
         format!(
             "{} {}",
             if self.id.value == curr { "◉" } else { "○" },
-            V::iter()
-                .filter(RadioOptionValue::selectable)
-                .collect::<Vec<V>>()[curr]
+            self.values[curr]
         )
     }
 
+    fn current(&self) -> String {
+        self.values.get(self.id.value).cloned().unwrap_or_default()
+    }
+
     fn length(&self) -> usize {
         self.id.length
     }
+
+    fn unmet_requirements(&self) -> Vec<String> {
+        self.unmet_requirements.clone()
+    }
 }
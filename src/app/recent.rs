@@ -0,0 +1,175 @@
+use crate::{
+    AppContext,
+    common::ProjectType,
+    history::{History, Record},
+};
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListState, Paragraph},
+};
+use ratatui_macros::constraints;
+/// What is picked in the list of the projects created before.
+pub(super) enum RecentOutput {
+    /// Fills the form of its project type with its answers.
+    Reuse(Record),
+    /// Fills the form, then asks to create the project right away.
+    Regenerate(Record),
+    Back,
+}
+/// The projects created before, newest first, with the answers and tools of
+/// the selected one.
+pub(super) struct Recent {
+    records:  Vec<Record>,
+    error:    Option<String>,
+    /// How many lines of the history are not records.
+    skipped:  usize,
+    selected: usize,
+}
+impl Recent {
+    /// Reads the history, keeping the records of `project_type` only when it
+    /// is the only one which can be created.
+    pub(super) fn new(context: &AppContext, project_type: Option<ProjectType>) -> Self {
+        let (history, error) = match History::load(context) {
+            Ok(history) => (history, None),
+            Err(error) => (History::default(), Some(format!("{error:#}"))),
+        };
+        Self {
+            records: history
+                .records
+                .into_iter()
+                .rev()
+                .filter(|record| project_type.is_none_or(|p| p == record.project_type))
+                .collect(),
+            error,
+            skipped: history.skipped.len(),
+            selected: 0,
+        }
+    }
+
+    pub(super) fn handle_keyevent(&mut self, key: KeyEvent) -> Option<RecentOutput> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.records.len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Enter => {
+                return self
+                    .records
+                    .get(self.selected)
+                    .cloned()
+                    .map(RecentOutput::Reuse);
+            }
+            KeyCode::Char('g') => {
+                return self
+                    .records
+                    .get(self.selected)
+                    .cloned()
+                    .map(RecentOutput::Regenerate);
+            }
+            KeyCode::Esc => return Some(RecentOutput::Back),
+            _ => {}
+        }
+        None
+    }
+
+    fn details(record: &Record) -> Vec<Line<'static>> {
+        let section = |title: &str| {
+            Line::styled(
+                title.to_string(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+        let width = record
+            .values
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .chain(record.tools.keys().map(|name| name.chars().count()))
+            .max()
+            .unwrap_or_default();
+        let mut lines = vec![
+            Line::raw(format!(
+                "{} at {}",
+                record.project_type,
+                record.created_at()
+            )),
+            Line::raw(record.path.display().to_string()),
+            Line::default(),
+            section("Answers"),
+        ];
+        lines.extend(
+            record
+                .values
+                .iter()
+                .map(|(label, value)| Line::raw(format!("  {label:<width$}  {value}"))),
+        );
+        lines.push(Line::default());
+        lines.push(section("Tools"));
+        lines.extend(
+            record
+                .tools
+                .iter()
+                .map(|(name, version)| Line::raw(format!("  {name:<width$}  {version}"))),
+        );
+        lines
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .title("Recent projects")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::LightBlue));
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+        let [inner_area, skipped_area] =
+            Layout::vertical(constraints![>=0,==u16::from(self.skipped > 0)]).areas(inner_area);
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{} unreadable lines of the history were skipped, see `project-setup history`",
+                self.skipped
+            ))
+            .style(Color::Yellow),
+            skipped_area,
+        );
+        if let Some(error) = &self.error {
+            frame.render_widget(Paragraph::new(error.clone()).style(Color::Red), inner_area);
+            return;
+        }
+        if self.records.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No project created yet")
+                    .style(Color::Gray)
+                    .centered(),
+                inner_area,
+            );
+            return;
+        }
+        let split_area = Layout::horizontal(constraints![==40%,>=0]).split(inner_area);
+        frame.render_stateful_widget(
+            List::new(
+                self.records
+                    .iter()
+                    .map(|record| format!("{}  {}", record.created_at(), record.name())),
+            )
+            .block(Block::default().borders(Borders::RIGHT))
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("» "),
+            split_area[0],
+            &mut ListState::default().with_selected(Some(self.selected)),
+        );
+        frame.render_widget(
+            Paragraph::new(Self::details(&self.records[self.selected])),
+            split_area[1].inner(Margin::new(1, 0)),
+        );
+    }
+}
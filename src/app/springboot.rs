@@ -1,29 +1,23 @@
 use super::{
-    Inner, InnerCommonState, InnerField, InnerFieldMapping, InnerHandleKeyEventOutput,
-    InnerTipLabel, PreparePermit, PrepareRecv, PrepareTrait, RadioOption, RadioOptionTrait,
-    handle_inner_keyevent,
+    Inner, InnerCommonState, InnerField, InnerHandleKeyEventOutput, PrepareRecv, PrepareTrait,
+    RadioOption, RadioOptionTrait, handle_inner_keyevent, handle_inner_paste, inner_restore,
+    inner_summary, render_inner_form,
 };
 use crate::{
-    EnumFunc, InnerState, LoopableNumberedEnum, RadioOption,
-    common::{Editor, ProjectType, Vcs},
-    features::{RequestMethod, download_file, unzip},
+    AppContext, InnerForm, InnerState,
+    common::{Editor, EditorSettings, Requirement, Vcs, expand_path, validate_directory},
+    features::{RequestMethod, download_file},
+    generate::{
+        Options, SpringBootOptions,
+        springboot::{Generator, JavaVersion, Language},
+    },
 };
-use anyhow::Result;
 use heck::ToSnakeCase;
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
-use ratatui::{
-    Frame,
-    crossterm::event::KeyEvent,
-    layout::{Layout, Rect},
-    style::Color,
-    widgets::{Block, BorderType, Borders, Paragraph},
-};
-use ratatui_macros::constraints;
+use ratatui::{Frame, crossterm::event::KeyEvent, layout::Rect};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{env, fmt::Debug, fs, path::PathBuf, sync::OnceLock};
-use strum_macros::{Display, EnumIter};
+use std::{fmt::Debug, fs, path::PathBuf, sync::Arc};
+use strum::IntoEnumIterator;
 #[derive(Debug, Serialize, Deserialize)]
 struct SpringInitializrMetadata {
     #[serde(rename = "type")]
@@ -79,23 +73,8 @@ struct TextOption {
     r#type:  String,
     default: String,
 }
-static METADATA: OnceLock<SpringInitializrMetadata> = OnceLock::new();
-#[derive(Display, Clone, Copy, FromPrimitive, EnumIter, ToPrimitive)]
-enum SpringBootField {
-    Name,
-    Generator,
-    GroupId,
-    ArtifactId,
-    BootVersion,
-    Language,
-    JavaVersion,
-    Editor,
-    Vcs,
-    Dependencies,
-    Path,
-}
 impl InnerField for SpringBootField {
-    fn validate_string(self, value: &mut str) -> String {
+    fn validate_string(self, value: &mut str, _: &AppContext) -> String {
         if value.is_empty() {
             return format!("{} cannot be empty", self.to_string().to_snake_case());
         }
@@ -107,166 +86,118 @@ impl InnerField for SpringBootField {
                     String::new()
                 }
             }
+            Self::Path => validate_directory(value),
             _ => String::new(),
         }
     }
+
+    fn is_path(self) -> bool {
+        matches!(self, Self::Path)
+    }
 }
-#[derive(
-    Clone,
-    Copy,
-    Default,
-    Display,
-    Debug,
-    ToPrimitive,
-    FromPrimitive,
-    LoopableNumberedEnum,
-    EnumIter,
-    PartialEq,
-    RadioOption,
-)]
-#[numbered_enum(loop_within = 2)]
-enum Generator {
-    #[default]
-    Maven,
-    Gradle,
-}
-#[derive(Clone, Copy, Default, Display, Debug, EnumIter, PartialEq, RadioOption, EnumFunc)]
-enum Language {
-    #[default]
-    #[enum_func(extension("java"))]
-    Java,
-    #[enum_func(extension("kt"))]
-    Kotlin,
-}
-#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, RadioOption)]
-enum JavaVersion {
-    #[strum(to_string = "25")]
-    TwentyFive,
-    #[strum(to_string = "21")]
-    TwentyOne,
-    #[default]
-    #[strum(to_string = "17")]
-    Seventeen,
-    #[strum(to_string = "8")]
-    Eight,
-}
-#[derive(Clone, InnerState)]
+#[derive(Clone, InnerForm, InnerState)]
 pub(crate) struct SpringBootInner {
-    name:         String,
-    generator:    RadioOption<Generator>,
-    group_id:     String,
-    artifact_id:  String,
-    boot_version: String,
-    language:     RadioOption<Language>,
-    java_version: RadioOption<JavaVersion>,
-    editor:       RadioOption<Editor>,
-    vcs:          RadioOption<Vcs>,
-    dependencies: Vec<String>,
-    path:         PathBuf,
-    common_state: InnerCommonState,
+    #[field(label = "name", tip = "Please input the name of this project", kind = text)]
+    name:            String,
+    #[field(label = "generator", tip = "Use arrow keys to select generator", kind = radio)]
+    generator:       RadioOption<Generator>,
+    #[field(label = "group_id", tip = "Please input the group_id of this project", kind = text)]
+    group_id:        String,
+    #[field(
+        label = "artifact_id",
+        tip = "Please input the artifact_id of this project",
+        kind = text
+    )]
+    artifact_id:     String,
+    #[field(
+        label = "boot_version",
+        tip = "Please input the boot_version of this project",
+        kind = text
+    )]
+    boot_version:    String,
+    #[field(label = "language", tip = "Use arrow keys to select language", kind = radio)]
+    language:        RadioOption<Language>,
+    #[field(label = "java_version", tip = "Use arrow keys to select java_version", kind = radio)]
+    java_version:    RadioOption<JavaVersion>,
+    #[field(label = "editor", tip = "Use arrow keys to select editor", kind = radio)]
+    editor:          RadioOption<Editor>,
+    #[field(
+        label = "editor_settings",
+        tip = "Use arrow keys to select whether to generate editor settings",
+        kind = radio
+    )]
+    editor_settings: RadioOption<EditorSettings>,
+    #[field(label = "vcs", tip = "Use arrow keys to select vcs tool", kind = radio)]
+    vcs:             RadioOption<Vcs>,
+    #[field(label = "dependencies", tip = "The dependencies of this project", kind = list)]
+    dependencies:    Vec<String>,
+    #[field(
+        label = "path",
+        tip = "Please input the path of this project, Tab completes it, Ctrl-O browses",
+        kind = text
+    )]
+    path:            String,
+    common_state:    InnerCommonState,
 }
 impl SpringBootInner {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(context: &Arc<AppContext>) -> Self {
         Self {
-            name:         "demo".to_string(),
-            generator:    RadioOption::default(),
-            group_id:     "com.example".to_string(),
-            artifact_id:  "demo".to_string(),
-            boot_version: "3.3.0".to_string(),
-            language:     RadioOption::default(),
-            java_version: RadioOption::default(),
-            editor:       RadioOption::default(),
-            vcs:          RadioOption::default(),
-            dependencies: vec![String::new()],
-            path:         env::current_dir().unwrap(),
-            common_state: InnerCommonState::new::<SpringBootField>(),
+            name:            "demo".to_string(),
+            generator:       RadioOption::new(context),
+            group_id:        "com.example".to_string(),
+            artifact_id:     "demo".to_string(),
+            boot_version:    "3.3.0".to_string(),
+            language:        RadioOption::new(context),
+            java_version:    RadioOption::new(context),
+            editor:          RadioOption::new(context),
+            editor_settings: RadioOption::new(context),
+            vcs:             RadioOption::new(context),
+            dependencies:    vec![String::new()],
+            path:            context.config.work_dir.display().to_string(),
+            common_state:    InnerCommonState::new::<SpringBootField>(context),
         }
     }
-}
-impl InnerFieldMapping<SpringBootField> for SpringBootInner {
-    fn get_focus_field_mut(&mut self, field: SpringBootField) -> Option<&mut String> {
-        match field {
-            SpringBootField::Name => Some(&mut self.name),
-            SpringBootField::GroupId => Some(&mut self.group_id),
-            SpringBootField::ArtifactId => Some(&mut self.artifact_id),
-            SpringBootField::BootVersion => Some(&mut self.boot_version),
-            _ => None,
-        }
+
+    pub(super) fn unmet_requirements(context: &AppContext) -> Vec<String> {
+        RadioOption::<JavaVersion>::new(context).unmet_requirements()
     }
 
-    fn get_field(&self, field: SpringBootField) -> &dyn Debug {
-        match field {
-            SpringBootField::Name => &self.name,
-            SpringBootField::Generator => &self.generator.value,
-            SpringBootField::GroupId => &self.group_id,
-            SpringBootField::ArtifactId => &self.artifact_id,
-            SpringBootField::BootVersion => &self.boot_version,
-            SpringBootField::Language => &self.language.value,
-            SpringBootField::JavaVersion => &self.java_version.value,
-            SpringBootField::Editor => &self.editor.value,
-            SpringBootField::Vcs => &self.vcs.value,
-            SpringBootField::Dependencies => &self.dependencies,
-            SpringBootField::Path => &self.path,
-        }
+    /// The tool versions the options are checked against.
+    pub(super) fn requirements() -> Vec<Requirement> {
+        JavaVersion::iter()
+            .map(|version| Requirement::new(version.requires()))
+            .collect()
     }
 
-    fn get_radio(&mut self, field: SpringBootField) -> Option<&mut dyn RadioOptionTrait> {
-        match field {
-            SpringBootField::Generator => Some(&mut self.generator),
-            SpringBootField::Language => Some(&mut self.language),
-            SpringBootField::JavaVersion => Some(&mut self.java_version),
-            SpringBootField::Editor => Some(&mut self.editor),
-            SpringBootField::Vcs => Some(&mut self.vcs),
-            _ => None,
+    fn options(&self) -> SpringBootOptions {
+        SpringBootOptions {
+            name:            self.name.clone(),
+            generator:       self.generator.value,
+            group_id:        self.group_id.clone(),
+            artifact_id:     self.artifact_id.clone(),
+            boot_version:    self.boot_version.clone(),
+            language:        self.language.value,
+            java_version:    self.java_version.value,
+            dependencies:    self.dependencies.clone(),
+            vcs:             self.vcs.value,
+            editor_settings: (self.editor_settings.value == EditorSettings::Generate)
+                .then_some(self.editor.value),
         }
     }
 }
-impl InnerTipLabel for SpringBootInner {
-    fn tips() -> &'static [&'static str] {
-        &[
-            "Please input the name of this project",
-            "Use arrow keys to select generator",
-            "Please input the group_id of this project",
-            "Please input the artifact_id of this project",
-            "Please input the boot_version of this project",
-            "Use arrow keys to select language",
-            "Use arrow keys to select java_version",
-            "Use arrow keys to select editor",
-            "Use arrow keys to select vcs tool",
-            "Please input the dependencies of this project",
-            "Please input the path of this project",
-        ]
+impl PrepareTrait for SpringBootInner {
+    fn header() -> &'static str {
+        "SpringBoot preparing"
     }
 
-    fn labels() -> &'static [&'static str] {
-        &[
-            "name",
-            "generator",
-            "group_id",
-            "artifact_id",
-            "boot_version",
-            "language",
-            "java_version",
-            "editor",
-            "vcs",
-            "dependencies",
-            "path",
-        ]
-    }
-}
-impl PrepareTrait for SpringBootInner {
-    async fn prepare(permit: &mut PreparePermit<'_>, offset: f64) -> bool {
-        let mut recv = PrepareRecv::new(offset);
-        if !ProjectType::SpringBoot.in_args() {
-            recv.send_done(permit);
-            return true;
-        }
-        let metadata_file = env::temp_dir().join("springboot_metadata.json");
-        recv.send_ok(permit);
+    async fn prepare(context: Arc<AppContext>, mut recv: PrepareRecv) -> bool {
+        let metadata_file = context.config.cache_dir.join("springboot_metadata.json");
+        recv.send_ok();
         if metadata_file.exists() {
-            recv.send_ok(permit);
+            recv.send_ok();
         } else {
             let r = download_file(
+                context.executables(),
                 "https://start.spring.io/metadata/client",
                 &RequestMethod::GET,
                 &[],
@@ -274,31 +205,31 @@ impl PrepareTrait for SpringBootInner {
             );
             match r {
                 Ok(()) => {
-                    recv.send_ok(permit);
+                    recv.send_ok();
                 }
                 Err(error) => {
-                    recv.send_error(permit, error);
+                    recv.send_error(error);
                     return false;
                 }
             }
         }
         match fs::read_to_string(metadata_file).map_err(anyhow::Error::new) {
             Ok(data) => {
-                recv.send_ok(permit);
+                recv.send_ok();
                 match serde_json::from_str(&data).map_err(anyhow::Error::new) {
                     Ok(metadata) => {
-                        let _ = METADATA.set(metadata);
-                        recv.send_ok(permit);
+                        context.set_cache::<SpringInitializrMetadata>(metadata);
+                        recv.send_ok();
                         true
                     }
                     Err(error) => {
-                        recv.send_error(permit, error);
+                        recv.send_error(error);
                         false
                     }
                 }
             }
             Err(error) => {
-                recv.send_error(permit, error);
+                recv.send_error(error);
                 false
             }
         }
@@ -317,88 +248,7 @@ impl PrepareTrait for SpringBootInner {
 }
 impl Inner for SpringBootInner {
     fn render(&mut self, f: &mut Frame, focus_right_side: bool, area: Rect) {
-        let labels = Self::labels();
-        // 表单布局 - 垂直排列输入框
-        let form_layout = Layout::vertical(constraints![==5;6]).split(area);
-        let split_line_layout = Layout::horizontal(constraints![==1/2;2]);
-        let split_label_input_layout = Layout::horizontal(constraints![==20,>=0]);
-        let split_tip_input_error_layout = Layout::vertical(constraints![<=1,<=3,<=1]);
-        for i in (0 .. labels.len()).step_by(2) {
-            let line_layout = split_line_layout.split(form_layout[i / 2]);
-            for side in 0 .. 2 {
-                let index = i + side;
-                if index == labels.len() {
-                    break;
-                }
-                let side_line_layout = line_layout[side];
-                let label_input_area = split_label_input_layout.split(side_line_layout);
-                f.render_widget(
-                    Paragraph::new(labels[index]).centered().block(
-                        Block::default()
-                            .borders(Borders::all())
-                            .border_type(BorderType::Thick),
-                    ),
-                    split_tip_input_error_layout.split(label_input_area[0])[1],
-                );
-                let focus_block = Block::new()
-                    .borders(Borders::ALL)
-                    .border_style(
-                        if index == self.common_state.focus_index.value && focus_right_side {
-                            Color::Red
-                        } else {
-                            Color::default()
-                        },
-                    )
-                    .border_type(BorderType::Thick);
-                if focus_right_side && index == self.common_state.focus_index.value {
-                    f.render_widget(
-                        Paragraph::new(Self::tips()[index])
-                            .style(Color::Blue)
-                            .centered(),
-                        split_tip_input_error_layout.split(label_input_area[1])[0],
-                    );
-                }
-                let field = SpringBootField::from_usize(index).unwrap();
-                if let Some(r) = self.get_radio(field) {
-                    f.render_widget(
-                        Paragraph::new(
-                            (0 .. r.length())
-                                .map(|curr| r.get_symbol(curr))
-                                .collect::<Vec<String>>()
-                                .join("    "),
-                        )
-                        .centered()
-                        .block(focus_block),
-                        split_tip_input_error_layout.split(label_input_area[1])[1],
-                    );
-                    continue;
-                }
-                let field_value = self.get_field(field);
-                let field_string_value = format!("{field_value:?}").replace('"', "");
-                f.render_widget(
-                    Paragraph::new(if field_string_value.is_empty() {
-                        format!("Please input {}", labels[index])
-                    } else {
-                        field_string_value.clone()
-                    })
-                    .centered()
-                    .style(if field_string_value.is_empty() {
-                        Color::Gray
-                    } else {
-                        Color::default()
-                    })
-                    .block(focus_block),
-                    split_tip_input_error_layout.split(label_input_area[1])[1],
-                );
-                if !self.common_state.error_messages[index].is_empty() {
-                    f.render_widget(
-                        Paragraph::new(self.common_state.error_messages[index].clone())
-                            .style(Color::Red),
-                        split_tip_input_error_layout.split(label_input_area[1])[2],
-                    );
-                }
-            }
-        }
+        render_inner_form::<Self, SpringBootField>(self, f, focus_right_side, area);
     }
 
     fn bottom_help_message(&self) -> String {
@@ -410,47 +260,23 @@ impl Inner for SpringBootInner {
         handle_inner_keyevent(self, key)
     }
 
-    fn create_and_edit(&self) -> Result<()> {
-        let project_path = self.path.join(&self.name);
-        fs::create_dir_all(&project_path)?;
-        self.vcs.value.init_vcs_repo(&self.name, &self.path)?;
-        let params = [
-            ("groupId", self.group_id.clone()),
-            ("artifactId", self.artifact_id.clone()),
-            (
-                "type",
-                format!(
-                    "{}-project",
-                    self.generator.value.to_string().to_lowercase()
-                ),
-            ),
-            ("name", self.name.clone()),
-            ("language", self.language.value.to_string().to_lowercase()),
-            ("javaVersion", self.java_version.value.to_string()),
-            ("bootVersion", self.boot_version.clone()),
-            ("baseDir", self.name.clone()),
-            ("dependencies", self.dependencies.join(",")),
-        ];
-        let temp_zip_file = env::temp_dir().join("starter.zip");
-        download_file(
-            "https://start.spring.io/starter.zip",
-            &RequestMethod::POST,
-            &params,
-            &temp_zip_file,
-        )?;
-        unzip(&temp_zip_file, &self.path)?;
-        fs::remove_file(&temp_zip_file)?;
-        self.editor.value.run(
-            self.path.join(&self.name),
-            format!(
-                "src/main/{}/{}/{}/{}Application.{}",
-                self.language.value.to_string().to_lowercase(),
-                self.group_id.replace('.', "/"),
-                self.artifact_id,
-                self.artifact_id[0 .. 1].to_uppercase() + &self.artifact_id[1 ..],
-                self.language.value.extension()
-            ),
-        )?;
-        Ok(())
+    fn handle_paste(&mut self, text: &str) {
+        handle_inner_paste(self, text);
+    }
+
+    fn summary(&mut self) -> Vec<(String, String)> {
+        inner_summary::<Self, SpringBootField>(self)
+    }
+
+    fn restore(&mut self, values: &[(String, String)]) {
+        inner_restore::<Self, SpringBootField>(self, values);
+    }
+
+    fn generation(&self) -> Option<(Options, PathBuf)> {
+        Some((self.options().into(), expand_path(&self.path)))
+    }
+
+    fn editor(&self) -> Editor {
+        self.editor.value
     }
 }
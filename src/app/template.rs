@@ -0,0 +1,478 @@
+use super::{
+    Choice, FormFields, Inner, InnerCommonState, InnerHandleKeyEventOutput, PrepareRecv,
+    PrepareTrait, RadioOption, RadioOptionTrait, form_restore, form_summary, handle_form_keyevent,
+    handle_form_paste, render_form,
+};
+use crate::{
+    AppContext,
+    common::{Editor, Vcs, expand_path, validate_directory},
+    generate::{
+        Options, TemplateOptions,
+        import::import_with,
+        template::{MANIFEST, PromptKind, Template, Templates},
+    },
+};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    crossterm::event::KeyEvent,
+    prelude::Rect,
+    style::Color,
+    widgets::{Paragraph, Wrap},
+};
+use std::{mem, path::PathBuf, sync::Arc, thread};
+use tokio::sync::oneshot::{self, Receiver, error::TryRecvError};
+/// The answer of a prompt as it is edited.
+enum Answer {
+    Text(String),
+    Radio(Choice),
+}
+/// A template imported from a git repository or a directory, loaded in the
+/// background once its location is confirmed.
+#[derive(Default)]
+struct Import {
+    location: String,
+    /// The location of the template loaded, or being loaded.
+    loaded:   String,
+    loading:  Option<Receiver<anyhow::Result<Template>>>,
+    error:    Option<String>,
+    /// Restored once the template is loaded, as its prompts are not known
+    /// before.
+    pending:  Vec<(String, String)>,
+}
+/// Where the template of the form comes from.
+enum Source {
+    /// One of the templates of the templates directory, chosen with a radio.
+    Local(Choice),
+    Import(Import),
+}
+/// What the field at an index of the form is.
+enum Slot {
+    Template,
+    Name,
+    Prompt(usize),
+    Editor,
+    Vcs,
+    Path,
+}
+/// A project rendered from one of the templates of the templates directory,
+/// or from an imported one, its form is built from the prompts of the
+/// selected template.
+pub(super) struct TemplateInner {
+    /// The templates to choose from, or the imported one once loaded.
+    templates:    Vec<Template>,
+    source:       Source,
+    name:         String,
+    answers:      Vec<Answer>,
+    editor:       RadioOption<Editor>,
+    vcs:          RadioOption<Vcs>,
+    path:         String,
+    common_state: InnerCommonState,
+}
+impl TemplateInner {
+    pub(super) fn new(context: &Arc<AppContext>) -> Self {
+        let templates = context.cache::<Templates>().unwrap_or_default();
+        let mut inner = Self {
+            templates:    templates.found.clone(),
+            source:       Source::Local(
+                Choice::new(
+                    templates
+                        .found
+                        .iter()
+                        .map(|template| template.name.clone())
+                        .collect(),
+                    None,
+                )
+                .with_notes(templates.errors.clone()),
+            ),
+            name:         String::new(),
+            answers:      vec![],
+            editor:       RadioOption::new(context),
+            vcs:          RadioOption::new(context),
+            path:         context.config.work_dir.display().to_string(),
+            common_state: InnerCommonState::with_count(0, context),
+        };
+        inner.select_template();
+        inner
+    }
+
+    /// A form importing a cargo-generate or copier template.
+    pub(super) fn import(context: &Arc<AppContext>) -> Self {
+        let mut inner = Self::new(context);
+        inner.templates.clear();
+        inner.source = Source::Import(Import::default());
+        inner.select_template();
+        inner
+    }
+
+    /// Why some directories of the templates directory are not templates.
+    pub(super) fn unmet_requirements(context: &AppContext) -> Vec<String> {
+        context
+            .cache::<Templates>()
+            .map(|templates| templates.errors.clone())
+            .unwrap_or_default()
+    }
+
+    fn selected(&self) -> Option<&Template> {
+        match &self.source {
+            Source::Local(choice) => self.templates.get(choice.index()),
+            Source::Import(_) => self.templates.first(),
+        }
+    }
+
+    /// Whether there is no template to choose, only the templates directory
+    /// can be empty.
+    fn no_templates(&self) -> bool {
+        matches!(self.source, Source::Local(_)) && self.templates.is_empty()
+    }
+
+    /// Starts loading the imported template unless it is already.
+    fn load(&mut self) {
+        let Source::Import(import) = &mut self.source else {
+            return;
+        };
+        if import.location.is_empty() || import.location == import.loaded {
+            return;
+        }
+        let (sender, receiver) = oneshot::channel();
+        let context = self.common_state.context.clone();
+        let location = import.location.clone();
+        // clone 可能很慢, 在后台进行, 渲染时再取结果
+        thread::spawn(move || {
+            let _ = sender.send(import_with(&context, &location));
+        });
+        import.loaded.clone_from(&import.location);
+        import.loading = Some(receiver);
+        import.error = None;
+        self.common_state.error_messages[0] = self.validate(0);
+    }
+
+    /// Rebuilds the form once the imported template is loaded.
+    fn poll(&mut self) {
+        let Source::Import(import) = &mut self.source else {
+            return;
+        };
+        let Some(receiver) = &mut import.loading else {
+            return;
+        };
+        let loaded = match receiver.try_recv() {
+            Err(TryRecvError::Empty) => return,
+            Ok(loaded) => loaded,
+            Err(TryRecvError::Closed) => Err(anyhow::anyhow!("loading stopped")),
+        };
+        import.loading = None;
+        self.templates = match loaded {
+            Ok(template) => vec![template],
+            Err(error) => {
+                import.error = Some(format!("{error:#}"));
+                vec![]
+            }
+        };
+        let pending = mem::take(&mut import.pending);
+        self.select_template();
+        if !self.templates.is_empty() {
+            self.common_state.focus_index.value = 1;
+            form_restore(self, &pending);
+        }
+        self.common_state.error_messages[0] = self.validate(0);
+    }
+
+    /// Asks the prompts of the selected template, with their defaults.
+    fn select_template(&mut self) {
+        self.answers = self
+            .selected()
+            .map(|template| {
+                template
+                    .manifest
+                    .prompts
+                    .iter()
+                    .map(|prompt| match prompt.kind {
+                        PromptKind::Radio => Answer::Radio(Choice::new(
+                            prompt.options.clone(),
+                            prompt.default.as_deref(),
+                        )),
+                        PromptKind::Bool => Answer::Radio(Choice::new(
+                            vec!["true".to_string(), "false".to_string()],
+                            Some(prompt.default.as_deref().unwrap_or("false")),
+                        )),
+                        PromptKind::Text | PromptKind::List => {
+                            Answer::Text(prompt.default.clone().unwrap_or_default())
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let context = self.common_state.context.clone();
+        self.common_state = InnerCommonState::with_count(self.answers.len() + 5, &context);
+    }
+
+    fn slot(&self, index: usize) -> Slot {
+        match index {
+            0 => Slot::Template,
+            1 => Slot::Name,
+            _ if index < self.answers.len() + 2 => Slot::Prompt(index - 2),
+            _ if index == self.answers.len() + 2 => Slot::Editor,
+            _ if index == self.answers.len() + 3 => Slot::Vcs,
+            _ => Slot::Path,
+        }
+    }
+
+    fn options(&self) -> Option<TemplateOptions> {
+        let template = self.selected()?;
+        let mut options = TemplateOptions::new(self.name.clone(), template.clone());
+        for (prompt, answer) in template.manifest.prompts.iter().zip(&self.answers) {
+            let answer = match answer {
+                Answer::Text(text) => text.clone(),
+                Answer::Radio(choice) => choice.current(),
+            };
+            options
+                .values
+                .insert(prompt.name.clone(), prompt.value(&answer));
+        }
+        options.vcs = self.vcs.value;
+        Some(options)
+    }
+}
+impl FormFields for TemplateInner {
+    fn count(&self) -> usize {
+        self.answers.len() + 5
+    }
+
+    fn label(&self, index: usize) -> String {
+        match self.slot(index) {
+            Slot::Template => match self.source {
+                Source::Local(_) => "template".to_string(),
+                Source::Import(_) => "source".to_string(),
+            },
+            Slot::Name => "name".to_string(),
+            Slot::Prompt(prompt) => self
+                .selected()
+                .map(|template| template.manifest.prompts[prompt].label().to_string())
+                .unwrap_or_default(),
+            Slot::Editor => "editor".to_string(),
+            Slot::Vcs => "vcs".to_string(),
+            Slot::Path => "path".to_string(),
+        }
+    }
+
+    fn tip(&self, index: usize) -> String {
+        match self.slot(index) {
+            Slot::Template => match &self.source {
+                Source::Local(_) => self
+                    .selected()
+                    .and_then(|template| template.manifest.description.clone())
+                    .unwrap_or_else(|| "Use arrow keys to select the template".to_string()),
+                Source::Import(import) if import.loading.is_some() => {
+                    format!("Loading {}", import.loaded)
+                }
+                Source::Import(_) => "Please input the git url or the path of a cargo-generate or \
+                                      copier template, Enter loads it"
+                    .to_string(),
+            },
+            Slot::Name => "Please input the name of this project".to_string(),
+            Slot::Prompt(prompt) => self
+                .selected()
+                .map(|template| template.manifest.prompts[prompt].tip())
+                .unwrap_or_default(),
+            Slot::Editor => "Use arrow keys to select editor".to_string(),
+            Slot::Vcs => "Use arrow keys to select vcs tool".to_string(),
+            Slot::Path => "Please input the path of this project, Tab completes it, Ctrl-O browses"
+                .to_string(),
+        }
+    }
+
+    fn value(&self, index: usize) -> String {
+        match self.slot(index) {
+            Slot::Name => self.name.clone(),
+            Slot::Prompt(prompt) => match &self.answers[prompt] {
+                Answer::Text(text) => text.clone(),
+                Answer::Radio(choice) => choice.current(),
+            },
+            Slot::Path => self.path.clone(),
+            Slot::Template => match &self.source {
+                Source::Local(_) => String::new(),
+                Source::Import(import) => import.location.clone(),
+            },
+            Slot::Editor | Slot::Vcs => String::new(),
+        }
+    }
+
+    fn text_mut(&mut self, index: usize) -> Option<&mut String> {
+        match self.slot(index) {
+            Slot::Name => Some(&mut self.name),
+            Slot::Prompt(prompt) => match &mut self.answers[prompt] {
+                Answer::Text(text) => Some(text),
+                Answer::Radio(_) => None,
+            },
+            Slot::Path => Some(&mut self.path),
+            Slot::Template => match &mut self.source {
+                Source::Local(_) => None,
+                Source::Import(import) => Some(&mut import.location),
+            },
+            Slot::Editor | Slot::Vcs => None,
+        }
+    }
+
+    fn radio_mut(&mut self, index: usize) -> Option<&mut dyn RadioOptionTrait> {
+        match self.slot(index) {
+            Slot::Template => match &mut self.source {
+                Source::Local(choice) => Some(choice),
+                Source::Import(_) => None,
+            },
+            Slot::Prompt(prompt) => match &mut self.answers[prompt] {
+                Answer::Radio(choice) => Some(choice),
+                Answer::Text(_) => None,
+            },
+            Slot::Editor => Some(&mut self.editor),
+            Slot::Vcs => Some(&mut self.vcs),
+            Slot::Name | Slot::Path => None,
+        }
+    }
+
+    fn validate(&mut self, index: usize) -> String {
+        match self.slot(index) {
+            Slot::Template => match &self.source {
+                Source::Import(import) if import.location.is_empty() => {
+                    "source cannot be empty".to_string()
+                }
+                Source::Import(import) if import.loading.is_some() => {
+                    "the template is loading".to_string()
+                }
+                Source::Import(import) if import.location != import.loaded => {
+                    "press Enter to load the template".to_string()
+                }
+                Source::Import(Import {
+                    error: Some(error), ..
+                }) => error.clone(),
+                _ => String::new(),
+            },
+            Slot::Name if self.name.is_empty() => "name cannot be empty".to_string(),
+            Slot::Prompt(prompt) => match (&self.answers[prompt], self.selected()) {
+                (Answer::Text(text), Some(template)) => {
+                    template.manifest.prompts[prompt].validate(text)
+                }
+                _ => String::new(),
+            },
+            Slot::Path => validate_directory(&self.path),
+            _ => String::new(),
+        }
+    }
+
+    fn is_path(&self, index: usize) -> bool {
+        match self.slot(index) {
+            Slot::Template => matches!(self.source, Source::Import(_)),
+            Slot::Path => true,
+            _ => false,
+        }
+    }
+
+    fn state_mut(&mut self) -> &mut InnerCommonState {
+        &mut self.common_state
+    }
+}
+impl PrepareTrait for TemplateInner {
+    fn header() -> &'static str {
+        "Template preparing"
+    }
+
+    async fn prepare(context: Arc<AppContext>, mut recv: PrepareRecv) -> bool {
+        context.set_cache(Templates::load(&context.config.templates_dir));
+        recv.send_ok();
+        true
+    }
+
+    fn descs() -> Vec<String> {
+        vec!["Reading templates".to_string()]
+    }
+}
+impl Inner for TemplateInner {
+    fn render(&mut self, f: &mut Frame, focus_right_side: bool, area: Rect) {
+        self.poll();
+        if self.no_templates() {
+            let dir = &self.common_state.context.config.templates_dir;
+            let mut lines = vec![format!(
+                "No templates found, add a directory with a {MANIFEST} to {}",
+                dir.display()
+            )];
+            if let Source::Local(choice) = &self.source {
+                lines.extend(choice.unmet_requirements());
+            }
+            f.render_widget(
+                Paragraph::new(lines.join("\n"))
+                    .style(Color::Gray)
+                    .centered()
+                    .wrap(Wrap { trim: true }),
+                area,
+            );
+            return;
+        }
+        render_form(self, f, focus_right_side, area);
+    }
+
+    fn bottom_help_message(&self) -> String {
+        if self.no_templates() {
+            return String::new();
+        }
+        "tab: focus next item | shift+tab: focus prev item | Enter: confirm to create project | "
+            .to_string()
+    }
+
+    fn handle_keyevent(&mut self, key: KeyEvent) -> InnerHandleKeyEventOutput {
+        if self.no_templates() {
+            return InnerHandleKeyEventOutput::default();
+        }
+        let on_source =
+            self.common_state.focus_index.value == 0 && self.common_state.browser.is_none();
+        if let Source::Import(_) = self.source
+            && on_source
+            && key.code == KeyCode::Enter
+        {
+            self.load();
+            return InnerHandleKeyEventOutput::default();
+        }
+        let selected = self.selected().map(|template| template.name.clone());
+        let output = handle_form_keyevent(self, key);
+        match self.source {
+            Source::Local(_)
+                if self.selected().map(|template| template.name.clone()) != selected =>
+            {
+                self.select_template();
+            }
+            // 离开来源字段时开始加载
+            Source::Import(_) if on_source && self.common_state.focus_index.value != 0 => {
+                self.load();
+            }
+            _ => {}
+        }
+        output
+    }
+
+    fn handle_paste(&mut self, text: &str) {
+        if !self.no_templates() {
+            handle_form_paste(self, text);
+        }
+    }
+
+    fn summary(&mut self) -> Vec<(String, String)> {
+        form_summary(self)
+    }
+
+    fn restore(&mut self, values: &[(String, String)]) {
+        // 模板决定了其余的字段, 先选中模板再填其余的值
+        form_restore(self, values);
+        self.select_template();
+        form_restore(self, values);
+        if let Source::Import(import) = &mut self.source {
+            import.pending = values.to_vec();
+            self.load();
+        }
+    }
+
+    fn generation(&self) -> Option<(Options, PathBuf)> {
+        Some((self.options()?.into(), expand_path(&self.path)))
+    }
+
+    fn editor(&self) -> Editor {
+        self.editor.value
+    }
+}
@@ -0,0 +1,234 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Margin, Position, Rect},
+    text::Line,
+};
+/// Editing state of a text field, the value itself is kept by the inner.
+#[derive(Clone)]
+pub(crate) struct TextInput {
+    /// In chars, from the start of the value, past its end when the value was
+    /// not edited yet.
+    cursor: usize,
+    /// The value and cursor before each edit, typing a word counts as one.
+    undo:   Vec<(String, usize)>,
+    typing: bool,
+}
+impl Default for TextInput {
+    fn default() -> Self {
+        Self {
+            cursor: usize::MAX,
+            undo:   vec![],
+            typing: false,
+        }
+    }
+}
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+fn byte_index(value: &str, cursor: usize) -> usize {
+    value
+        .char_indices()
+        .nth(cursor)
+        .map_or(value.len(), |(index, _)| index)
+}
+impl TextInput {
+    fn cursor(&self, value: &str) -> usize {
+        self.cursor.min(value.chars().count())
+    }
+
+    /// Where the word before the cursor starts.
+    fn word_start(&self, value: &str) -> usize {
+        let chars: Vec<char> = value.chars().collect();
+        let mut cursor = self.cursor(value);
+        while cursor > 0 && !is_word(chars[cursor - 1]) {
+            cursor -= 1;
+        }
+        while cursor > 0 && is_word(chars[cursor - 1]) {
+            cursor -= 1;
+        }
+        cursor
+    }
+
+    /// Where the word after the cursor ends.
+    fn word_end(&self, value: &str) -> usize {
+        let chars: Vec<char> = value.chars().collect();
+        let mut cursor = self.cursor(value);
+        while cursor < chars.len() && !is_word(chars[cursor]) {
+            cursor += 1;
+        }
+        while cursor < chars.len() && is_word(chars[cursor]) {
+            cursor += 1;
+        }
+        cursor
+    }
+
+    fn snapshot(&mut self, value: &str, typing: bool) {
+        if !(typing && self.typing) {
+            self.undo.push((value.to_string(), self.cursor(value)));
+        }
+        self.typing = typing;
+    }
+
+    /// Replaces the chars between `start` and `end` with `text`.
+    fn replace(&mut self, value: &mut String, start: usize, end: usize, text: &str) {
+        let range = byte_index(value, start) .. byte_index(value, end);
+        value.replace_range(range, text);
+        self.cursor = start + text.chars().count();
+    }
+
+    /// Inserts `text` at the cursor, without its line breaks.
+    pub(crate) fn paste(&mut self, value: &mut String, text: &str) {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        self.snapshot(value, false);
+        let cursor = self.cursor(value);
+        self.replace(value, cursor, cursor, &text);
+    }
+
+    /// Replaces the whole `value` with `text`, leaving the cursor at its end.
+    pub(crate) fn set(&mut self, value: &mut String, text: &str) {
+        self.snapshot(value, false);
+        let length = value.chars().count();
+        self.replace(value, 0, length, text);
+    }
+
+    /// Edits `value` or moves the cursor, returns whether the key was used.
+    pub(crate) fn handle_keyevent(&mut self, value: &mut String, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let cursor = self.cursor(value);
+        let length = value.chars().count();
+        match key.code {
+            KeyCode::Char('z') if ctrl => {
+                if let Some((previous, cursor)) = self.undo.pop() {
+                    *value = previous;
+                    self.cursor = cursor;
+                }
+                self.typing = false;
+                return true;
+            }
+            KeyCode::Char('w') if ctrl => {
+                let start = self.word_start(value);
+                self.snapshot(value, false);
+                self.replace(value, start, cursor, "");
+                return true;
+            }
+            KeyCode::Backspace if ctrl || alt => {
+                let start = self.word_start(value);
+                self.snapshot(value, false);
+                self.replace(value, start, cursor, "");
+                return true;
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.snapshot(value, false);
+                self.replace(value, 0, cursor, "");
+                return true;
+            }
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = length,
+            KeyCode::Char('b') if alt => self.cursor = self.word_start(value),
+            KeyCode::Char('f') if alt => self.cursor = self.word_end(value),
+            KeyCode::Char(c) if !ctrl && !alt => {
+                self.snapshot(value, is_word(c));
+                self.replace(value, cursor, cursor, &c.to_string());
+                return true;
+            }
+            KeyCode::Backspace if cursor > 0 => {
+                self.snapshot(value, false);
+                self.replace(value, cursor - 1, cursor, "");
+                return true;
+            }
+            KeyCode::Delete if cursor < length => {
+                self.snapshot(value, false);
+                self.replace(value, cursor, cursor + 1, "");
+                self.cursor = cursor;
+                return true;
+            }
+            KeyCode::Left if ctrl || alt => self.cursor = self.word_start(value),
+            KeyCode::Right if ctrl || alt => self.cursor = self.word_end(value),
+            KeyCode::Left => self.cursor = cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (cursor + 1).min(length),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = length,
+            KeyCode::Backspace | KeyCode::Delete => {}
+            _ => return false,
+        }
+        self.typing = false;
+        true
+    }
+
+    /// Where the cursor is drawn when `value` is centered in the bordered
+    /// `area`.
+    pub(crate) fn cursor_position(&self, value: &str, area: Rect) -> Position {
+        let inner = area.inner(Margin::new(1, 1));
+        let width = u16::try_from(Line::raw(value).width()).unwrap_or(u16::MAX);
+        let before = &value[.. byte_index(value, self.cursor(value))];
+        let before = u16::try_from(Line::raw(before).width()).unwrap_or(u16::MAX);
+        Position::new(
+            (inner.x + inner.width.saturating_sub(width) / 2 + before)
+                .min(inner.right().saturating_sub(1)),
+            inner.y,
+        )
+    }
+}
+#[cfg(test)]
+mod text_input_test {
+    use super::TextInput;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    fn press(input: &mut TextInput, value: &mut String, keys: &[(KeyCode, KeyModifiers)]) {
+        for (code, modifiers) in keys {
+            input.handle_keyevent(value, KeyEvent::new(*code, *modifiers));
+        }
+    }
+    #[test]
+    fn test_text_input_editing() {
+        let none = KeyModifiers::NONE;
+        let ctrl = KeyModifiers::CONTROL;
+        let mut input = TextInput::default();
+        let mut value = String::new();
+        input.paste(&mut value, "com.exmple\n");
+        assert_eq!(value, "com.exmple");
+        press(
+            &mut input,
+            &mut value,
+            &[
+                (KeyCode::Left, none),
+                (KeyCode::Left, none),
+                (KeyCode::Left, none),
+                (KeyCode::Left, none),
+                (KeyCode::Char('a'), none),
+            ],
+        );
+        assert_eq!(value, "com.example");
+        press(
+            &mut input,
+            &mut value,
+            &[
+                (KeyCode::Home, none),
+                (KeyCode::Delete, none),
+                (KeyCode::Char('C'), none),
+                (KeyCode::End, none),
+                (KeyCode::Char('w'), ctrl),
+            ],
+        );
+        assert_eq!(value, "Com.");
+        press(&mut input, &mut value, &[(KeyCode::Char('z'), ctrl)]);
+        assert_eq!(value, "Com.example");
+        press(
+            &mut input,
+            &mut value,
+            &[(KeyCode::Left, ctrl), (KeyCode::Char('u'), ctrl)],
+        );
+        assert_eq!(value, "example");
+        press(
+            &mut input,
+            &mut value,
+            &[
+                (KeyCode::Char('z'), ctrl),
+                (KeyCode::Char('z'), ctrl),
+                (KeyCode::Char('z'), ctrl),
+                (KeyCode::Char('z'), ctrl),
+            ],
+        );
+        assert_eq!(value, "com.exmple");
+    }
+}
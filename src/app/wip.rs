@@ -1,10 +1,12 @@
 use super::{Inner, InnerHandleKeyEventOutput};
+use crate::{common::Editor, generate::Options};
 use ratatui::{
     crossterm::event::KeyEvent,
     layout::Layout,
     style::{Style, Stylize},
 };
 use ratatui_macros::constraints;
+use std::path::PathBuf;
 use tui_big_text::{BigText, PixelSize};
 pub(crate) struct WipInner {}
 impl Inner for WipInner {
@@ -32,7 +34,15 @@ impl Inner for WipInner {
         InnerHandleKeyEventOutput::default()
     }
 
-    fn create_and_edit(&self) -> anyhow::Result<()> {
-        Ok(())
+    fn summary(&mut self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    fn generation(&self) -> Option<(Options, PathBuf)> {
+        None
+    }
+
+    fn editor(&self) -> Editor {
+        Editor::NotNeed
     }
 }
@@ -1,10 +1,35 @@
 use crate::common::ProjectType;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 /// A TUI to help you setup a project easily.
-#[derive(Parser, Clone, Copy)]
+#[derive(Parser, Clone, Copy, Default)]
 #[command(version, about)]
 pub struct Args {
     /// the only project type to create (skips project selection)
     #[arg(short, long)]
-    pub(crate) project_type: Option<ProjectType>,
+    pub(crate) project_type:     Option<ProjectType>,
+    /// preview the files and commands of the project instead of creating it
+    #[arg(long)]
+    pub(crate) dry_run:          bool,
+    /// only look the tools up in PATH, without running them for their
+    /// versions
+    #[arg(long, global = true)]
+    pub(crate) no_version_probe: bool,
+    #[command(subcommand)]
+    pub command:                 Option<Command>,
+}
+#[derive(Subcommand, Clone, Copy)]
+pub enum Command {
+    /// Report the tools found in this environment and what missing ones
+    /// disable
+    Doctor {
+        /// print the report as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the projects created so far, oldest first
+    History {
+        /// print every record, with its answers and tool versions, as json
+        #[arg(long)]
+        json: bool,
+    },
 }
@@ -1,13 +1,15 @@
+use crate::AppContext;
 use crate::app::RadioOptionValue;
-use crate::common::ExecutableEnumTrait;
+use crate::common::{ExecutableEnumTrait, ExecutableMap};
 use anyhow::Result;
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
-use project_setup_derive::{ExecutableEnum, LoopableNumberedEnum};
+use project_setup_derive::{EnumFunc, ExecutableEnum, LoopableNumberedEnum};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
 use std::{
+    ffi::OsString,
     io::Error,
-    path::PathBuf,
-    process::{Command, ExitStatus},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
 };
 use strum_macros::{Display, EnumIter};
 #[derive(
@@ -15,46 +17,116 @@ use strum_macros::{Display, EnumIter};
     Debug,
     Default,
     LoopableNumberedEnum,
-    FromPrimitive,
-    ToPrimitive,
     Clone,
     Display,
     EnumIter,
     PartialEq,
     ExecutableEnum,
+    EnumFunc,
 )]
-#[numbered_enum(loop_within = 6)]
-pub(crate) enum Editor {
+#[enum_func(
+    // GUI editors are detached so that the terminal is released immediately.
+    detached: bool = false
+)]
+pub enum Editor {
     #[default]
     #[exe("")]
     NotNeed,
     #[exe("vim")]
     Vim,
     #[exe("code")]
+    #[enum_func(detached(true))]
     VSCode,
     #[exe("nvim")]
     Neovim,
     #[exe("idea")]
+    #[enum_func(detached(true))]
     Idea,
     #[exe("clion")]
+    #[enum_func(detached(true))]
     Clion,
     #[exe("rustrover")]
+    #[enum_func(detached(true))]
     Rustrover,
 }
+#[cfg(test)]
+const _: () = {
+    assert!(matches!(Editor::Clion.next(), Editor::Rustrover));
+    assert!(matches!(Editor::Rustrover.next(), Editor::NotNeed));
+    assert!(matches!(Editor::NotNeed.prev(), Editor::Rustrover));
+};
 impl RadioOptionValue for Editor {
-    fn selectable(&self) -> bool {
-        super::Executable::executable(&self.exe())
+    fn selectable(&self, context: &AppContext) -> bool {
+        context.executables().executable(&self.exe())
     }
 }
 impl Editor {
-    pub(crate) fn run(self, project_path: PathBuf, main: String) -> Result<ExitStatus, Error> {
+    /// Arguments opening `project_path` as a workspace with `main` focused.
+    fn args(self, project_path: &Path, main: &str) -> Vec<OsString> {
+        let main_path = project_path.join(main).into_os_string();
         match self {
-            Self::NotNeed => Ok(ExitStatus::default()),
-            _ => Command::new(self.exe())
-                .arg(main)
-                .current_dir(project_path)
-                .spawn()?
-                .wait(),
+            Self::NotNeed => vec![],
+            Self::Vim | Self::Neovim => vec![main.into()],
+            Self::VSCode => vec![project_path.into(), "-g".into(), main_path],
+            Self::Idea | Self::Clion | Self::Rustrover => {
+                vec![project_path.into(), "--line".into(), "1".into(), main_path]
+            }
+        }
+    }
+
+    pub(crate) fn run(
+        self,
+        executables: &ExecutableMap,
+        project_path: PathBuf,
+        main: String,
+    ) -> Result<ExitStatus, Error> {
+        if self == Self::NotNeed {
+            return Ok(ExitStatus::default());
+        }
+        let mut command = Command::new(executables.path(&self.exe()));
+        command
+            .args(self.args(&project_path, &main))
+            .current_dir(&project_path);
+        if self.detached() {
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            #[cfg(unix)]
+            command.process_group(0);
+            command.spawn()?;
+            Ok(ExitStatus::default())
+        } else {
+            command.spawn()?.wait()
+        }
+    }
+}
+#[cfg(test)]
+mod editor_test {
+    use super::Editor;
+    use std::path::Path;
+    #[test]
+    fn test_args() {
+        let args = |editor: Editor| {
+            editor
+                .args(Path::new("/tmp/demo"), "src/main.rs")
+                .into_iter()
+                .map(|arg| arg.into_string().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert!(args(Editor::NotNeed).is_empty());
+        assert_eq!(args(Editor::Vim), ["src/main.rs"]);
+        assert_eq!(args(Editor::Neovim), ["src/main.rs"]);
+        assert_eq!(
+            args(Editor::VSCode),
+            ["/tmp/demo", "-g", "/tmp/demo/src/main.rs"]
+        );
+        for editor in [Editor::Idea, Editor::Clion, Editor::Rustrover] {
+            assert_eq!(
+                args(editor),
+                ["/tmp/demo", "--line", "1", "/tmp/demo/src/main.rs"],
+                "{editor}"
+            );
         }
     }
 }
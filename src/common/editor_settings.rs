@@ -0,0 +1,81 @@
+use super::Editor;
+use crate::AppContext;
+use crate::app::RadioOptionValue;
+use crate::generate::{TemplateValue, Variables};
+use strum_macros::{Display, EnumIter};
+#[derive(Copy, Debug, Default, Clone, Display, EnumIter, PartialEq)]
+pub(crate) enum EditorSettings {
+    #[default]
+    Generate,
+    Skip,
+}
+impl RadioOptionValue for EditorSettings {
+    fn selectable(&self, _: &AppContext) -> bool {
+        true
+    }
+}
+/// What the editor settings are generated for.
+pub(crate) enum EditorProject {
+    Cargo {
+        name:    String,
+        library: bool,
+    },
+    Cmake {
+        name: String,
+        cpp:  bool,
+    },
+    SpringBoot {
+        name:       String,
+        main_class: String,
+    },
+}
+impl EditorProject {
+    /// The variables the editor templates are rendered with, `project` tells
+    /// which of the others are set.
+    pub(crate) fn variables(&self) -> Variables {
+        let variables: Vec<(&str, TemplateValue)> = match self {
+            Self::Cargo { name, library } => vec![
+                ("project", "cargo".into()),
+                ("name", name.as_str().into()),
+                ("library", (*library).into()),
+            ],
+            Self::Cmake { name, cpp } => vec![
+                ("project", "cmake".into()),
+                ("name", name.as_str().into()),
+                ("cpp", (*cpp).into()),
+            ],
+            Self::SpringBoot { name, main_class } => vec![
+                ("project", "springboot".into()),
+                ("name", name.as_str().into()),
+                ("main_class", main_class.as_str().into()),
+            ],
+        };
+        variables
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+}
+impl Editor {
+    /// The project settings of this editor, so that the new project can be
+    /// built and debugged right away: the path relative to the project and
+    /// the built-in template it is rendered from.
+    pub(crate) fn settings_templates(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::NotNeed | Self::Vim => &[],
+            Self::VSCode => &[
+                (".vscode/settings.json", "editor/vscode/settings.json"),
+                (".vscode/extensions.json", "editor/vscode/extensions.json"),
+                (".vscode/launch.json", "editor/vscode/launch.json"),
+            ],
+            Self::Idea | Self::Clion | Self::Rustrover => &[(
+                ".idea/runConfigurations/{{ name }}.xml",
+                "editor/idea/runConfiguration.xml",
+            )],
+            Self::Neovim => &[
+                (".nvim.lua", "editor/neovim/nvim.lua"),
+                (".lazy.lua", "editor/neovim/lazy.lua"),
+            ],
+        }
+    }
+}
@@ -1,28 +1,52 @@
-use crate::app::{PreparePermit, PrepareRecv, PrepareTrait};
+use crate::{
+    AppContext,
+    app::{PrepareRecv, PrepareTrait},
+};
 use std::collections::HashMap;
-use std::process::Command;
-use std::sync::OnceLock;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use strum::IntoEnumIterator;
+use tokio::{process::Command, task::JoinSet, time::timeout};
 pub(crate) trait ExecutableEnumTrait {
     fn exe(&self) -> String;
 }
-static EXECUTABLE_CACHE: OnceLock<HashMap<String, bool>> = OnceLock::new();
+/// How long a single `--version` probe may take before it is given up.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+#[derive(Debug, Clone, Default)]
+struct ExecutableInfo {
+    path:    Option<PathBuf>,
+    version: Option<String>,
+}
+/// Every looked up tool, with the path and version it was found with.
+#[derive(Debug, Default)]
+pub(crate) struct ExecutableMap {
+    infos: HashMap<String, ExecutableInfo>,
+}
 pub(crate) struct Executable {}
 impl PrepareTrait for Executable {
-    async fn prepare(permit: &mut PreparePermit<'_>, offset: f64) -> bool {
-        let cvs: Vec<String> = Self::list(false);
-        let mut recv = PrepareRecv::new(offset);
-        let mut cache: HashMap<String, bool> = HashMap::new();
-        for ele in cvs {
-            let res = ele.is_empty()
-                || Command::new(ele.clone())
-                    .arg(if ele.eq("7z") { "--help" } else { "--version" })
-                    .output()
-                    .is_ok_and(|o| o.status.success());
-            cache.insert(ele, res);
-            recv.send_ok(permit);
+    fn header() -> &'static str {
+        "Environment checking"
+    }
+
+    async fn prepare(context: Arc<AppContext>, mut recv: PrepareRecv) -> bool {
+        let mut probes = JoinSet::new();
+        let probe_version = !context.args.no_version_probe;
+        for cmd in Self::list(false) {
+            probes.spawn(async move {
+                let info = Self::probe(&cmd, probe_version).await;
+                (cmd, info)
+            });
+        }
+        let mut infos: HashMap<String, ExecutableInfo> = HashMap::new();
+        while let Some(probed) = probes.join_next().await {
+            if let Ok((cmd, info)) = probed {
+                infos.insert(cmd, info);
+            }
+            recv.send_ok();
         }
-        let _ = EXECUTABLE_CACHE.set(cache);
+        context.set_executables(ExecutableMap { infos });
         true
     }
 
@@ -35,6 +59,7 @@ impl Executable {
         super::Vcs::iter()
             .map(|x| x.exe())
             .chain(super::Editor::iter().map(|x| x.exe()))
+            .chain(["cargo", "cmake", "java"].map(ToString::to_string))
             .chain(["curl", "wget", "unzip", "tar", "7z"].map(ToString::to_string))
             .map(|s| {
                 if descs {
@@ -49,7 +74,213 @@ impl Executable {
             .collect()
     }
 
-    pub(crate) fn executable(cmd: &str) -> bool {
-        *EXECUTABLE_CACHE.get().unwrap().get(cmd).unwrap_or(&false)
+    /// Every tool which is looked up, without the empty one of `NotNeed`.
+    pub(crate) fn tools() -> Vec<String> {
+        Self::list(false)
+            .into_iter()
+            .filter(|cmd| !cmd.is_empty())
+            .collect()
+    }
+
+    /// What cannot be chosen or created when `cmd` is missing.
+    pub(crate) fn disables(cmd: &str) -> Vec<String> {
+        let mut disables: Vec<String> = super::Vcs::iter()
+            .filter(|vcs| vcs.exe() == cmd)
+            .map(|vcs| format!("vcs {vcs}"))
+            .chain(
+                super::Editor::iter()
+                    .filter(|editor| editor.exe() == cmd)
+                    .map(|editor| format!("editor {editor}")),
+            )
+            .collect();
+        match cmd {
+            "cargo" => disables.push(format!("project type {}", super::ProjectType::Cargo)),
+            "git" => disables.push(format!(
+                "project type {}, unless the template is a local directory",
+                super::ProjectType::Import
+            )),
+            #[cfg(not(feature = "reqwest"))]
+            "curl" => disables.push(format!(
+                "project type {}, unless wget is found",
+                super::ProjectType::SpringBoot
+            )),
+            #[cfg(not(feature = "reqwest"))]
+            "wget" => disables.push(format!(
+                "project type {}, unless curl is found",
+                super::ProjectType::SpringBoot
+            )),
+            #[cfg(not(feature = "zip"))]
+            "unzip" => disables.push(format!(
+                "project type {}, unless 7z is found",
+                super::ProjectType::SpringBoot
+            )),
+            #[cfg(not(feature = "zip"))]
+            "7z" => disables.push(format!(
+                "project type {}, unless unzip is found",
+                super::ProjectType::SpringBoot
+            )),
+            _ => {}
+        }
+        disables
+    }
+
+    /// Arguments printing the version of `cmd`. Tools which would start a GUI
+    /// just to answer are not probed at all.
+    fn version_args(cmd: &str) -> Option<&'static [&'static str]> {
+        match cmd {
+            "git" | "vim" | "nvim" | "cargo" | "cmake" | "curl" | "wget" | "tar" => {
+                Some(&["--version"])
+            }
+            // `-version` is understood by every JDK, `--version` only since 9.
+            "java" => Some(&["-version"]),
+            "svn" => Some(&["--version", "--quiet"]),
+            "unzip" => Some(&["-v"]),
+            _ => None,
+        }
+    }
+
+    /// Looks `cmd` up, and runs it for its version unless `probe_version` is
+    /// off.
+    async fn probe(cmd: &str, probe_version: bool) -> ExecutableInfo {
+        let path = Self::resolve(cmd);
+        let version = match (&path, Self::version_args(cmd)) {
+            (Some(path), Some(args)) if probe_version => Self::probe_version(path, args).await,
+            _ => None,
+        };
+        ExecutableInfo { path, version }
+    }
+
+    async fn probe_version(path: &Path, args: &[&str]) -> Option<String> {
+        let output = timeout(
+            PROBE_TIMEOUT,
+            Command::new(path).args(args).kill_on_drop(true).output(),
+        )
+        .await
+        .ok()?
+        .ok()?;
+        // Some tools, like `java`, print their version to stderr.
+        [output.stdout, output.stderr].iter().find_map(|out| {
+            String::from_utf8_lossy(out)
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(ToString::to_string)
+        })
+    }
+
+    /// Looks `cmd` up in `PATH` without running it.
+    pub(crate) fn resolve(cmd: &str) -> Option<PathBuf> {
+        if cmd.is_empty() {
+            return None;
+        }
+        env::split_paths(&env::var_os("PATH")?)
+            .flat_map(|dir| Self::candidates(&dir.join(cmd)))
+            .find(|candidate| Self::is_executable(candidate))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn candidates(path: &Path) -> Vec<PathBuf> {
+        vec![path.to_path_buf()]
+    }
+
+    #[cfg(target_os = "windows")]
+    fn candidates(path: &Path) -> Vec<PathBuf> {
+        let pathext = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+        std::iter::once(path.to_path_buf())
+            .chain(pathext.split(';').filter(|ext| !ext.is_empty()).map(|ext| {
+                let mut candidate = path.as_os_str().to_os_string();
+                candidate.push(ext);
+                PathBuf::from(candidate)
+            }))
+            .collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn is_executable(path: &Path) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn is_executable(path: &Path) -> bool {
+        path.is_file()
+    }
+}
+impl ExecutableMap {
+    /// Looks every tool up in `PATH` without probing its version, for when
+    /// there is no time to prepare.
+    pub(crate) fn resolve() -> Self {
+        Self {
+            infos: Executable::list(false)
+                .into_iter()
+                .map(|cmd| {
+                    let info = ExecutableInfo {
+                        path:    Executable::resolve(&cmd),
+                        version: None,
+                    };
+                    (cmd, info)
+                })
+                .collect(),
+        }
+    }
+
+    /// Tools found at their own name, with the `--version` output of each.
+    #[cfg(test)]
+    pub(crate) fn with_versions(versions: &[(&str, &str)]) -> Self {
+        Self {
+            infos: versions
+                .iter()
+                .map(|(cmd, version)| {
+                    let info = ExecutableInfo {
+                        path:    Some(PathBuf::from(cmd)),
+                        version: Some((*version).to_string()),
+                    };
+                    ((*cmd).to_string(), info)
+                })
+                .collect(),
+        }
+    }
+
+    fn info(&self, cmd: &str) -> Option<&ExecutableInfo> {
+        self.infos.get(cmd)
+    }
+
+    pub(crate) fn executable(&self, cmd: &str) -> bool {
+        cmd.is_empty() || self.info(cmd).is_some_and(|info| info.path.is_some())
+    }
+
+    /// The absolute path `cmd` was resolved to, if it was found.
+    pub(crate) fn resolved(&self, cmd: &str) -> Option<&Path> {
+        self.info(cmd)?.path.as_deref()
+    }
+
+    /// The absolute path `cmd` was resolved to, falling back to `cmd` itself.
+    pub(crate) fn path(&self, cmd: &str) -> PathBuf {
+        self.resolved(cmd)
+            .map_or_else(|| PathBuf::from(cmd), Path::to_path_buf)
+    }
+
+    /// The first line `cmd` printed for its version, if it was probed.
+    pub(crate) fn version(&self, cmd: &str) -> Option<&str> {
+        self.info(cmd)?.version.as_deref()
+    }
+}
+#[cfg(test)]
+mod executable_test {
+    use super::{Executable, ExecutableMap};
+    use crate::common::Vcs;
+    use std::path::Path;
+    #[tokio::test]
+    async fn test_probe_without_version() {
+        let info = Executable::probe("git", false).await;
+        assert!(info.version.is_none());
+        let executables = ExecutableMap::resolve();
+        let command = Vcs::Git.init_command(&executables, Path::new(".")).unwrap();
+        assert_eq!(command.get_program(), executables.path("git"));
+        assert_eq!(
+            info.path,
+            executables.resolved("git").map(Path::to_path_buf)
+        );
     }
 }
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use std::{
+    env, fs,
+    path::{self, MAIN_SEPARATOR, Path, PathBuf},
+    process,
+};
+/// `value` with a leading `~` replaced by the home directory, relative paths
+/// being resolved against the current directory.
+pub(crate) fn expand_path(value: &str) -> PathBuf {
+    let home = || env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"));
+    let path = if value == "~" {
+        home().map_or_else(|| PathBuf::from(value), PathBuf::from)
+    } else if let Some(rest) = value
+        .strip_prefix("~/")
+        .or_else(|| value.strip_prefix("~\\"))
+        && let Some(home) = home()
+    {
+        PathBuf::from(home).join(rest)
+    } else {
+        PathBuf::from(value)
+    };
+    path::absolute(&path).unwrap_or(path)
+}
+/// An error message unless `value` is an existing directory, checked on every
+/// edit so nothing is written to it.
+pub(crate) fn validate_directory(value: &str) -> String {
+    let path = expand_path(value);
+    if !path.exists() {
+        return format!("{} does not exist", path.display());
+    }
+    if !path.is_dir() {
+        return format!("{} is not a directory", path.display());
+    }
+    String::new()
+}
+/// Fails unless a file can be created in `dir`, probed once before creating
+/// a project there.
+pub(crate) fn writable_directory(dir: &Path) -> Result<()> {
+    // 权限位不可靠 (比如 root 或 ACL), 直接尝试写入
+    let probe = dir.join(format!(".project-setup-{}", process::id()));
+    fs::File::create(&probe).with_context(|| format!("{} is not writable", dir.display()))?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+/// The directories in `dir`, sorted, hidden ones last.
+pub(crate) fn subdirectories(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_by_key(|name| (name.starts_with('.'), name.clone()));
+    names
+}
+/// `value` completed as far as the directories it may name agree, `None` when
+/// nothing can be added.
+pub(crate) fn complete_directory(value: &str) -> Option<String> {
+    let (parent, prefix) = match value.rfind(['/', MAIN_SEPARATOR]) {
+        Some(index) => (&value[..= index], &value[index + 1 ..]),
+        None => ("", value),
+    };
+    let dir = if parent.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_path(parent)
+    };
+    let matches: Vec<String> = subdirectories(&dir)
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .filter(|name| prefix.starts_with('.') || !name.starts_with('.'))
+        .collect();
+    let first = matches.first()?;
+    let mut common = first.clone();
+    for name in &matches[1 ..] {
+        let length = common
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a.len_utf8())
+            .sum();
+        common.truncate(length);
+    }
+    if matches.len() == 1 {
+        common.push(MAIN_SEPARATOR);
+    }
+    (common != prefix).then(|| format!("{parent}{common}"))
+}
+#[cfg(test)]
+mod path_test {
+    use super::{
+        MAIN_SEPARATOR, complete_directory, expand_path, validate_directory, writable_directory,
+    };
+    use std::{env, fs, process};
+    #[test]
+    fn test_complete_directory() {
+        let root = env::temp_dir().join(format!("project-setup-path-{}", process::id()));
+        for dir in ["alpha", "alpine", "beta", ".hidden"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("alfa.txt"), "").unwrap();
+        let root = format!("{}{MAIN_SEPARATOR}", root.display());
+        let complete = |value: &str| complete_directory(&format!("{root}{value}"));
+        assert_eq!(complete("a"), Some(format!("{root}alp")));
+        assert_eq!(
+            complete("alph"),
+            Some(format!("{root}alpha{MAIN_SEPARATOR}"))
+        );
+        assert_eq!(complete("b"), Some(format!("{root}beta{MAIN_SEPARATOR}")));
+        assert_eq!(complete("alp"), None);
+        assert_eq!(complete("z"), None);
+        assert!(validate_directory(&format!("{root}beta")).is_empty());
+        assert!(!validate_directory(&format!("{root}gamma")).is_empty());
+        assert!(!validate_directory(&format!("{root}alfa.txt")).is_empty());
+        writable_directory(&expand_path(&format!("{root}beta"))).unwrap();
+        assert_eq!(fs::read_dir(format!("{root}beta")).unwrap().count(), 0);
+        assert_eq!(
+            expand_path("beta"),
+            env::current_dir().unwrap().join("beta")
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
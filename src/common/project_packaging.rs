@@ -1,12 +1,7 @@
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
 use project_setup_derive::LoopableNumberedEnum;
-use std::fmt::Debug;
-use strum::Display;
-
-#[derive(Debug, Clone, Default, Display, FromPrimitive, ToPrimitive, LoopableNumberedEnum)]
+use strum_macros::Display;
+#[derive(Debug, Clone, Default, Display, LoopableNumberedEnum)]
 #[allow(dead_code)]
-#[numbered_enum(loop_within = 7)]
 enum ProjectPackaging {
     #[default]
     NotNeed,
@@ -17,3 +12,14 @@ enum ProjectPackaging {
     Nix,
     Ubuntu,
 }
+#[cfg(test)]
+const _: () = {
+    assert!(matches!(
+        ProjectPackaging::Ubuntu.next(),
+        ProjectPackaging::NotNeed
+    ));
+    assert!(matches!(
+        ProjectPackaging::NotNeed.prev(),
+        ProjectPackaging::Ubuntu
+    ));
+};
@@ -1,7 +1,6 @@
-use clap::{Parser, ValueEnum};
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
+use clap::ValueEnum;
 use project_setup_derive::LoopableNumberedEnum;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter};
 #[derive(
     Debug,
@@ -11,25 +10,28 @@ use strum_macros::{Display, EnumIter};
     EnumIter,
     Display,
     PartialEq,
-    FromPrimitive,
-    ToPrimitive,
     LoopableNumberedEnum,
     ValueEnum,
+    Serialize,
+    Deserialize,
 )]
-#[numbered_enum(loop_within = 4)]
 pub(crate) enum ProjectType {
     #[default]
     SpringBoot,
     Cmake,
     Maven,
     Cargo,
+    Template,
+    Import,
 }
-impl ProjectType {
-    pub(crate) fn in_args(self) -> bool {
-        if let Some(p) = crate::Args::parse().project_type {
-            p == self
-        } else {
-            true
-        }
-    }
-}
+#[cfg(test)]
+const _: () = {
+    assert!(matches!(
+        ProjectType::Import.next(),
+        ProjectType::SpringBoot
+    ));
+    assert!(matches!(
+        ProjectType::SpringBoot.prev(),
+        ProjectType::Import
+    ));
+};
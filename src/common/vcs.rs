@@ -1,26 +1,16 @@
+use crate::AppContext;
 use crate::app::RadioOptionValue;
-use crate::common::ExecutableEnumTrait;
-use anyhow::Error;
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
+use crate::common::{ExecutableEnumTrait, ExecutableMap};
 use project_setup_derive::{ExecutableEnum, LoopableNumberedEnum};
-use std::{path::PathBuf, process::Command};
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
 use strum_macros::{Display, EnumIter};
 #[derive(
-    Copy,
-    Debug,
-    Default,
-    Display,
-    Clone,
-    EnumIter,
-    PartialEq,
-    FromPrimitive,
-    ToPrimitive,
-    LoopableNumberedEnum,
-    ExecutableEnum,
+    Copy, Debug, Default, Display, Clone, EnumIter, PartialEq, LoopableNumberedEnum, ExecutableEnum,
 )]
-#[numbered_enum(loop_within = 3)]
-pub(crate) enum Vcs {
+pub enum Vcs {
     #[default]
     #[exe("")]
     NotNeed,
@@ -29,29 +19,48 @@ pub(crate) enum Vcs {
     #[exe("svn")]
     Svn,
 }
+#[cfg(test)]
+const _: () = {
+    assert!(matches!(Vcs::Svn.next(), Vcs::NotNeed));
+    assert!(matches!(Vcs::NotNeed.prev(), Vcs::Svn));
+};
 impl Vcs {
-    pub(crate) fn init_vcs_repo(self, name: &String, path: &PathBuf) -> Result<(), Error> {
-        match self {
-            Vcs::Git => Command::new("git")
-                .arg("init")
-                .current_dir(path.join(name))
-                .output()
-                .map_err(|e| Error::msg(format!("Failed to execute git init: {e}"))),
-            Vcs::Svn => Command::new("svnadmin")
-                .arg("create")
-                .arg(name)
-                .current_dir(path)
-                .output()
-                .map_err(|e| Error::msg(format!("Failed to execute svnadmin create: {e}"))),
+    /// The command turning the existing `project_path` into a repository,
+    /// run with the tools `executables` found.
+    pub(crate) fn init_command(
+        self,
+        executables: &ExecutableMap,
+        project_path: &Path,
+    ) -> Option<Command> {
+        let mut command = match self {
+            Vcs::Git => {
+                let mut command = Command::new(executables.path("git"));
+                command.arg("init").current_dir(project_path);
+                command
+            }
+            Vcs::Svn => {
+                // svnadmin 和找到的 svn 装在一起
+                let svnadmin = executables.resolved("svn").map_or_else(
+                    || PathBuf::from("svnadmin"),
+                    |svn| {
+                        svn.with_file_name("svnadmin")
+                            .with_extension(svn.extension().unwrap_or_default())
+                    },
+                );
+                let mut command = Command::new(svnadmin);
+                command.arg("create").arg(project_path);
+                command
+            }
             Vcs::NotNeed => {
-                return Ok(());
+                return None;
             }
-        }?; // The ? operator will early return if there's an error
-        Ok(())
+        };
+        command.stdin(Stdio::null());
+        Some(command)
     }
 }
 impl RadioOptionValue for Vcs {
-    fn selectable(&self) -> bool {
-        super::Executable::executable(&self.exe())
+    fn selectable(&self, context: &AppContext) -> bool {
+        context.executables().executable(&self.exe())
     }
 }
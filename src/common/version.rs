@@ -0,0 +1,104 @@
+use super::ExecutableMap;
+use std::{fmt::Display, str::FromStr};
+/// A `major.minor.patch` version, missing components are read as `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+impl Version {
+    /// Picks the first version looking token out of a `--version` output,
+    /// e.g. `1.85.0` out of `cargo 1.85.0 (d73d2caf9 2024-12-31)`.
+    pub(crate) fn from_output(output: &str) -> Option<Self> {
+        let start = output.find(|c: char| c.is_ascii_digit())?;
+        let token: String = output[start ..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        token
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .take(3)
+            .collect::<Vec<_>>()
+            .join(".")
+            .parse()
+            .ok()
+    }
+
+    /// The version of `cmd` found while probing executables.
+    pub(crate) fn installed(executables: &ExecutableMap, cmd: &str) -> Option<Self> {
+        Self::from_output(executables.version(cmd)?)
+    }
+}
+impl FromStr for Version {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, '.');
+        let mut next = || parts.next().map_or(Ok(0), str::parse);
+        Ok(Self {
+            major: next()?,
+            minor: next()?,
+            patch: next()?,
+        })
+    }
+}
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+/// A minimum version of a tool, written as `"<cmd> <version>"`.
+pub(crate) struct Requirement {
+    cmd:     String,
+    minimum: Version,
+}
+impl Requirement {
+    /// # Panics
+    /// If `requirement` is not written as `"<cmd> <version>"`.
+    pub(crate) fn new(requirement: &str) -> Self {
+        let (cmd, minimum) = requirement
+            .split_once(' ')
+            .expect("requirement is written as \"<cmd> <version>\"");
+        Self {
+            cmd:     cmd.to_string(),
+            minimum: minimum.parse().expect("minimum version is numeric"),
+        }
+    }
+
+    pub(crate) fn cmd(&self) -> &str {
+        &self.cmd
+    }
+
+    /// Explains why the installed tool is too old. A tool whose version is
+    /// unknown is never reported, as nothing can be told about it.
+    pub(crate) fn unmet(&self, executables: &ExecutableMap) -> Option<String> {
+        let installed = Version::installed(executables, &self.cmd)?;
+        (installed < self.minimum).then(|| {
+            format!(
+                "requires {} >= {}, found {installed}",
+                self.cmd, self.minimum
+            )
+        })
+    }
+}
+#[cfg(test)]
+mod version_test {
+    use super::Version;
+    #[test]
+    fn test_version_from_output() {
+        let version = |s: &str| Version::from_output(s).map(|v| v.to_string());
+        assert_eq!(
+            version("cargo 1.85.0 (d73d2caf9 2024-12-31)").as_deref(),
+            Some("1.85.0")
+        );
+        assert_eq!(version("cmake version 3.20").as_deref(), Some("3.20.0"));
+        assert_eq!(
+            version("openjdk version \"21.0.1\" 2023-10-17").as_deref(),
+            Some("21.0.1")
+        );
+        assert_eq!(version("no version here"), None);
+        assert!(Version::from_output("java 1.8.0").unwrap() < "25".parse().unwrap());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    Args,
+    common::{ExecutableMap, expand_path},
+};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    env,
+    path::PathBuf,
+    sync::{Arc, Mutex, OnceLock},
+};
+/// Where projects are created, downloads are cached and the history is kept.
+#[derive(Clone)]
+pub struct Config {
+    /// The directory new projects are created in by default.
+    pub work_dir:      PathBuf,
+    /// The directory downloaded files, like the SpringBoot metadata, are kept.
+    pub cache_dir:     PathBuf,
+    /// The directory templates are read from, one directory per template.
+    pub templates_dir: PathBuf,
+    /// The directory whose files replace the built-in templates with the
+    /// same relative path, like `cmake/CMakeLists.txt`.
+    pub overrides_dir: PathBuf,
+    /// The directory the history of the created projects is kept in.
+    pub data_dir:      PathBuf,
+}
+impl Default for Config {
+    fn default() -> Self {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map_or_else(|| expand_path("~/.config"), PathBuf::from)
+            .join("project-setup");
+        Self {
+            work_dir:      env::current_dir().unwrap_or_default(),
+            cache_dir:     env::temp_dir(),
+            templates_dir: config_dir.join("templates"),
+            overrides_dir: config_dir.join("builtin"),
+            data_dir:      env::var_os("XDG_DATA_HOME")
+                .map_or_else(|| expand_path("~/.local/share"), PathBuf::from)
+                .join("project-setup"),
+        }
+    }
+}
+type Cache = Arc<dyn Any + Send + Sync>;
+/// Everything the applications and inners share, handed to them explicitly
+/// instead of living in process wide globals.
+pub struct AppContext {
+    pub(crate) args:   Args,
+    pub(crate) config: Config,
+    executables:       OnceLock<ExecutableMap>,
+    caches:            Mutex<HashMap<TypeId, Cache>>,
+}
+impl Default for AppContext {
+    fn default() -> Self {
+        Self::new(Args::default())
+    }
+}
+impl AppContext {
+    #[must_use]
+    pub fn new(args: Args) -> Self {
+        Self {
+            args,
+            config: Config::default(),
+            executables: OnceLock::new(),
+            caches: Mutex::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// The executables found while preparing, empty until then.
+    pub(crate) fn executables(&self) -> &ExecutableMap {
+        static EMPTY: OnceLock<ExecutableMap> = OnceLock::new();
+        self.executables
+            .get()
+            .unwrap_or_else(|| EMPTY.get_or_init(ExecutableMap::default))
+    }
+
+    pub(crate) fn set_executables(&self, executables: ExecutableMap) {
+        let _ = self.executables.set(executables);
+    }
+
+    /// The value of type `T` a prepare stage stored, if any.
+    ///
+    /// # Panics
+    /// If a thread panicked while holding the caches.
+    pub(crate) fn cache<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.caches
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .and_then(|cache| cache.downcast().ok())
+    }
+
+    /// # Panics
+    /// If a thread panicked while holding the caches.
+    pub(crate) fn set_cache<T: Any + Send + Sync>(&self, value: T) {
+        self.caches
+            .lock()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+}
@@ -1,5 +1,4 @@
-#[cfg(not(feature = "reqwest"))]
-use crate::common::Executable;
+use crate::common::ExecutableMap;
 use anyhow::Error;
 #[cfg(feature = "reqwest")]
 use reqwest::blocking::Client;
@@ -15,6 +14,7 @@ pub(crate) enum RequestMethod {
 }
 #[cfg(not(feature = "reqwest"))]
 pub(crate) fn download_file(
+    executables: &ExecutableMap,
     url: &str,
     method: &RequestMethod,
     params: &[(&str, String)],
@@ -27,8 +27,8 @@ pub(crate) fn download_file(
         .collect::<Vec<_>>()
         .join("&");
     // 优先尝试 curl
-    if Executable::executable("curl") {
-        let output = Command::new("curl")
+    if executables.executable("curl") {
+        let output = Command::new(executables.path("curl"))
             .arg("--silent")
             .arg("--show-error")
             .arg("-X")
@@ -53,8 +53,8 @@ pub(crate) fn download_file(
         }
     }
     // 其次尝试 wget
-    else if Executable::executable("wget") {
-        let mut command = Command::new("wget");
+    else if executables.executable("wget") {
+        let mut command = Command::new(executables.path("wget"));
         command.arg("--quiet").arg("-O").arg(output);
         if *method == RequestMethod::POST {
             // POST 请求
@@ -81,6 +81,7 @@ pub(crate) fn download_file(
 }
 #[cfg(feature = "reqwest")]
 pub(crate) fn download_file(
+    _executables: &ExecutableMap,
     url: &str,
     method: &RequestMethod,
     params: &[(&str, String)],
@@ -1,5 +1,4 @@
-#[cfg(not(feature = "zip"))]
-use crate::common::Executable;
+use crate::common::ExecutableMap;
 use anyhow::Error;
 #[cfg(feature = "zip")]
 use std::fs::File;
@@ -35,41 +34,45 @@ fn path_converter(path: &PathBuf) -> PathBuf {
     }
 }
 #[cfg(not(feature = "zip"))]
-pub(crate) fn unzip(zip_path: &PathBuf, output_dir: &PathBuf) -> Result<(), Error> {
+pub(crate) fn unzip(
+    executables: &ExecutableMap,
+    zip_path: &PathBuf,
+    output_dir: &PathBuf,
+) -> Result<(), Error> {
     let zip_path = path_converter(zip_path);
     let output_dir = path_converter(output_dir);
     // 优先尝试 unzip (Linux/macOS/Windows if installed)
-    if Executable::executable("unzip") {
-        Command::new("unzip")
+    if executables.executable("unzip") {
+        Command::new(executables.path("unzip"))
             .arg("-q")
             .arg("-o")
             .arg(&zip_path)
             .arg("-d")
             .arg(&output_dir)
-            .status()?;
+            .output()?;
         return Ok(());
     }
     // 其次尝试 7z (跨平台)
-    if Executable::executable("7z") {
-        Command::new("7z")
+    if executables.executable("7z") {
+        Command::new(executables.path("7z"))
             .arg("x")
             .arg("-y")
             .arg(format!("-o{}", output_dir.display()))
             .arg(&zip_path)
-            .status()?;
+            .output()?;
         return Ok(());
     }
     // Windows 原生解压方式
     #[cfg(target_os = "windows")]
     {
         // 方法1: 使用 tar (Windows 10+ 内置)
-        if Executable::executable("tar") {
-            Command::new("tar")
+        if executables.executable("tar") {
+            Command::new(executables.path("tar"))
                 .arg("-xf")
                 .arg(&zip_path)
                 .arg("-C")
                 .arg(&output_dir)
-                .status()?;
+                .output()?;
             return Ok(());
         }
         // 方法2: 使用 PowerShell 的 Expand-Archive
@@ -82,7 +85,8 @@ pub(crate) fn unzip(zip_path: &PathBuf, output_dir: &PathBuf) -> Result<(), Erro
             .arg("-Command")
             .arg(&ps_script)
             .creation_flags(0x0800_0000) // CREATE_NO_WINDOW
-            .status()?;
+            .output()?
+            .status;
         if status.success() {
             return Ok(());
         }
@@ -92,7 +96,11 @@ pub(crate) fn unzip(zip_path: &PathBuf, output_dir: &PathBuf) -> Result<(), Erro
     ))
 }
 #[cfg(feature = "zip")]
-pub(crate) fn unzip(zip_path: &PathBuf, output_dir: &PathBuf) -> Result<(), Error> {
+pub(crate) fn unzip(
+    _executables: &ExecutableMap,
+    zip_path: &PathBuf,
+    output_dir: &PathBuf,
+) -> Result<(), Error> {
     let zip_file = File::open(zip_path)?;
     let mut archive = ZipArchive::new(zip_file)?;
     for i in 0 .. archive.len() {
@@ -0,0 +1,92 @@
+use super::{Generation, RadioOptionValue};
+use crate::{
+    AppContext, EnumFunc, RadioOption,
+    common::{Editor, EditorProject, Requirement, Vcs},
+};
+use anyhow::Result;
+use std::{path::Path, process::Command};
+use strum_macros::{Display, EnumIter};
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, RadioOption, EnumFunc)]
+#[enum_func(args: &'static [&'static str], main_file: &'static Path)]
+pub enum ProjectType {
+    #[default]
+    #[enum_func(args("--bin"), main_file("src/main.rs"))]
+    Executable,
+    #[enum_func(args("--lib"), main_file("src/lib.rs"))]
+    Library,
+}
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, EnumFunc)]
+#[enum_func(requires: Option<&'static str>)]
+pub enum Edition {
+    #[strum(to_string = "2015")]
+    Fifteen,
+    #[strum(to_string = "2018")]
+    #[enum_func(requires("cargo 1.31"))]
+    Eighteen,
+    #[strum(to_string = "2021")]
+    #[enum_func(requires("cargo 1.56"))]
+    TwentyOne,
+    #[default]
+    #[strum(to_string = "2024")]
+    #[enum_func(requires("cargo 1.85"))]
+    TwentyFour,
+}
+impl RadioOptionValue for Edition {
+    fn selectable(&self, context: &AppContext) -> bool {
+        self.unmet_requirement(context).is_none()
+    }
+
+    fn unmet_requirement(&self, context: &AppContext) -> Option<String> {
+        Requirement::new(self.requires()?).unmet(context.executables())
+    }
+}
+/// A cargo package, initialized by `cargo init`.
+#[derive(Debug, Clone)]
+pub struct CargoOptions {
+    pub name:            String,
+    pub project_type:    ProjectType,
+    pub edition:         Edition,
+    pub vcs:             Vcs,
+    /// The editor to write debug and run settings for.
+    pub editor_settings: Option<Editor>,
+}
+impl CargoOptions {
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name:            name.into(),
+            project_type:    ProjectType::default(),
+            edition:         Edition::default(),
+            vcs:             Vcs::default(),
+            editor_settings: None,
+        }
+    }
+
+    pub(super) fn generate(&self, generation: &mut Generation) -> Result<()> {
+        if let Some(mut command) = self
+            .vcs
+            .init_command(generation.executables(), generation.project_path())
+        {
+            generation.run(&mut command)?;
+        }
+        let mut command = Command::new(generation.executables().path("cargo"));
+        command
+            .arg("init")
+            .args(self.project_type.args())
+            .arg("--name")
+            .arg(&self.name)
+            .arg("--edition")
+            .arg(self.edition.to_string())
+            .current_dir(generation.project_path());
+        generation.run(&mut command)?;
+        generation.editor_settings(
+            self.editor_settings,
+            &EditorProject::Cargo {
+                name:    self.name.clone(),
+                library: self.project_type == ProjectType::Library,
+            },
+        )?;
+        generation.main_file(self.project_type.main_file());
+        Ok(())
+    }
+}
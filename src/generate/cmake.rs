@@ -0,0 +1,197 @@
+use super::{Generation, RadioOptionValue, Variables};
+use crate::{
+    AppContext, EnumFunc, RadioOption,
+    common::{Editor, EditorProject, ExecutableMap, Requirement, Vcs},
+};
+use anyhow::{Result, anyhow, bail};
+use std::path::Path;
+use strum_macros::{Display, EnumIter};
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, RadioOption)]
+pub enum ProjectType {
+    #[default]
+    Executable,
+    Library,
+}
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, EnumFunc)]
+#[enum_func(main_file: &'static Path)]
+pub enum Language {
+    #[default]
+    #[enum_func(main_file("main.c"), standard("C"), compilers("cc", "gcc", "clang"))]
+    C,
+    #[enum_func(
+        main_file("main.cpp"),
+        standard("CXX"),
+        compilers("c++", "g++", "clang++")
+    )]
+    Cpp,
+}
+/// The first cmake release which understands `CMAKE_<LANG>_STANDARD
+/// <standard>`, for every standard of every language.
+const STANDARDS: &[(Language, &str, &str)] = &[
+    (Language::C, "90", "cmake 3.1"),
+    (Language::C, "99", "cmake 3.1"),
+    (Language::C, "11", "cmake 3.1"),
+    (Language::C, "17", "cmake 3.21"),
+    (Language::C, "23", "cmake 3.21"),
+    (Language::Cpp, "98", "cmake 3.1"),
+    (Language::Cpp, "11", "cmake 3.1"),
+    (Language::Cpp, "14", "cmake 3.1"),
+    (Language::Cpp, "17", "cmake 3.8"),
+    (Language::Cpp, "20", "cmake 3.12"),
+    (Language::Cpp, "23", "cmake 3.20"),
+    (Language::Cpp, "26", "cmake 3.25"),
+];
+impl Language {
+    /// The standards cmake knows for this language, oldest first.
+    pub(crate) fn standards(self) -> impl Iterator<Item = &'static str> {
+        STANDARDS
+            .iter()
+            .filter(move |(language, ..)| *language == self)
+            .map(|(_, standard, _)| *standard)
+    }
+
+    /// # Errors
+    /// If cmake knows no such standard for this language.
+    pub(crate) fn standard_requirement(self, standard: &str) -> Result<Requirement> {
+        STANDARDS
+            .iter()
+            .find(|(language, other, _)| *language == self && *other == standard)
+            .map(|(.., requirement)| Requirement::new(requirement))
+            .ok_or_else(|| {
+                anyhow!(
+                    "{self} has no standard {standard}, use one of {}",
+                    self.standards().collect::<Vec<_>>().join(", ")
+                )
+            })
+    }
+}
+/// Languages a compiler was found for, cached once `CmakeInner` is prepared.
+pub(crate) struct Compilers(pub(crate) Vec<Language>);
+impl RadioOptionValue for Language {
+    fn selectable(&self, context: &AppContext) -> bool {
+        self.unmet_requirement(context).is_none()
+    }
+
+    fn unmet_requirement(&self, context: &AppContext) -> Option<String> {
+        (!context.cache::<Compilers>()?.0.contains(self))
+            .then(|| format!("no compiler found, tried {}", self.compilers().join(", ")))
+    }
+}
+/// A CMake project with a single target, written without running cmake.
+#[derive(Debug, Clone)]
+pub struct CmakeOptions {
+    pub name:                      String,
+    pub cmake_minimum_required:    String,
+    pub project_type:              ProjectType,
+    pub language:                  Language,
+    pub language_standard_version: String,
+    pub vcs:                       Vcs,
+    /// The editor to write debug and run settings for.
+    pub editor_settings:           Option<Editor>,
+}
+impl CmakeOptions {
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name:                      name.into(),
+            cmake_minimum_required:    "3.20".to_string(),
+            project_type:              ProjectType::default(),
+            language:                  Language::default(),
+            language_standard_version: "17".to_string(),
+            vcs:                       Vcs::default(),
+            editor_settings:           None,
+        }
+    }
+
+    /// Checks the standard against the language and the installed cmake.
+    pub(super) fn validate(&self, executables: &ExecutableMap) -> Result<()> {
+        let requirement = self
+            .language
+            .standard_requirement(&self.language_standard_version)?;
+        if let Some(unmet) = requirement.unmet(executables) {
+            bail!(
+                "{} standard {} {unmet}",
+                self.language,
+                self.language_standard_version
+            );
+        }
+        Ok(())
+    }
+
+    pub(super) fn generate(&self, generation: &mut Generation) -> Result<()> {
+        if let Some(mut command) = self
+            .vcs
+            .init_command(generation.executables(), generation.project_path())
+        {
+            generation.run(&mut command)?;
+        }
+        let main_file = self.language.main_file().display().to_string();
+        let variables = Variables::from([
+            ("name".to_string(), self.name.as_str().into()),
+            (
+                "cmake_minimum_required".to_string(),
+                self.cmake_minimum_required.as_str().into(),
+            ),
+            ("standard".to_string(), self.language.standard().into()),
+            (
+                "language_standard_version".to_string(),
+                self.language_standard_version.as_str().into(),
+            ),
+            (
+                "project_type".to_string(),
+                self.project_type.to_string().into(),
+            ),
+            ("main_file".to_string(), main_file.as_str().into()),
+        ]);
+        generation.write_template("CMakeLists.txt", "cmake/CMakeLists.txt", &variables)?;
+        generation.write_template(&main_file, &format!("cmake/{main_file}"), &variables)?;
+        generation.editor_settings(
+            self.editor_settings,
+            &EditorProject::Cmake {
+                name: self.name.clone(),
+                cpp:  self.language == Language::Cpp,
+            },
+        )?;
+        generation.main_file(self.language.main_file());
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod cmake_test {
+    use super::{CmakeOptions, Language};
+    use crate::common::ExecutableMap;
+    #[test]
+    fn test_standards() {
+        let executables = ExecutableMap::with_versions(&[("cmake", "cmake version 3.20.0")]);
+        let validate = |language, standard: &str| {
+            let mut options = CmakeOptions::new("hello");
+            options.language = language;
+            options.language_standard_version = standard.to_string();
+            options
+                .validate(&executables)
+                .map_err(|error| error.to_string())
+        };
+        assert_eq!(validate(Language::C, "11"), Ok(()));
+        assert_eq!(
+            validate(Language::C, "17"),
+            Err("C standard 17 requires cmake >= 3.21.0, found 3.20.0".to_string())
+        );
+        assert_eq!(
+            validate(Language::C, "23"),
+            Err("C standard 23 requires cmake >= 3.21.0, found 3.20.0".to_string())
+        );
+        assert_eq!(
+            validate(Language::C, "20"),
+            Err("C has no standard 20, use one of 90, 99, 11, 17, 23".to_string())
+        );
+        assert_eq!(validate(Language::Cpp, "17"), Ok(()));
+        assert_eq!(validate(Language::Cpp, "23"), Ok(()));
+        assert!(validate(Language::Cpp, "26").is_err());
+        assert!(validate(Language::Cpp, "90").is_err());
+        // 找不到 cmake 时不检查版本
+        let executables = ExecutableMap::default();
+        let mut options = CmakeOptions::new("hello");
+        options.language_standard_version = "23".to_string();
+        assert!(options.validate(&executables).is_ok());
+    }
+}
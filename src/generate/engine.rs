@@ -0,0 +1,184 @@
+use anyhow::Result;
+use heck::{ToKebabCase, ToLowerCamelCase, ToPascalCase, ToShoutySnakeCase, ToSnakeCase};
+use liquid_core::{
+    Filter, FilterReflection, ParseFilter, Runtime, Value, ValueView,
+    parser::{FilterArguments, ParameterReflection},
+};
+use minijinja::{Environment, UndefinedBehavior};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt, sync::LazyLock};
+/// What a variable of a template is rendered as.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TemplateValue {
+    Text(String),
+    Bool(bool),
+    List(Vec<String>),
+}
+impl fmt::Display for TemplateValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => f.write_str(text),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::List(values) => f.write_str(&values.join(", ")),
+        }
+    }
+}
+impl From<&str> for TemplateValue {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+impl From<String> for TemplateValue {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+impl From<bool> for TemplateValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+/// The variables a template is rendered with, by name.
+pub(crate) type Variables = BTreeMap<String, TemplateValue>;
+type Convert = fn(&str) -> String;
+/// The case conversions both syntaxes have as filters, named like
+/// cargo-generate names them.
+const CASES: &[(&str, Convert)] = &[
+    ("snake_case", |text| text.to_snake_case()),
+    ("kebab_case", |text| text.to_kebab_case()),
+    ("pascal_case", |text| text.to_pascal_case()),
+    ("upper_camel_case", |text| text.to_pascal_case()),
+    ("camel_case", |text| text.to_lower_camel_case()),
+    ("lower_camel_case", |text| text.to_lower_camel_case()),
+    ("shouty_snake_case", |text| text.to_shouty_snake_case()),
+];
+/// Jinja, for the built-in templates, the local ones and copier's.
+static JINJA: LazyLock<Environment<'static>> = LazyLock::new(|| {
+    let mut environment = Environment::new();
+    environment.set_undefined_behavior(UndefinedBehavior::Strict);
+    environment.set_keep_trailing_newline(true);
+    // 只有标签的行不留下空行
+    environment.set_trim_blocks(true);
+    environment.set_lstrip_blocks(true);
+    for &(name, convert) in CASES {
+        environment.add_filter(name, move |text: &str| convert(text));
+    }
+    environment
+});
+/// A case conversion as a liquid filter.
+#[derive(Clone, Copy)]
+struct CaseFilter(&'static str, Convert);
+impl fmt::Debug for CaseFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+impl fmt::Display for CaseFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+impl FilterReflection for CaseFilter {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn description(&self) -> &'static str {
+        ""
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+impl ParseFilter for CaseFilter {
+    fn parse(&self, _: FilterArguments) -> liquid_core::Result<Box<dyn Filter>> {
+        Ok(Box::new(*self))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+impl Filter for CaseFilter {
+    fn evaluate(&self, input: &dyn ValueView, _: &dyn Runtime) -> liquid_core::Result<Value> {
+        Ok(Value::scalar(self.1(&input.to_kstr())))
+    }
+}
+/// Liquid, for cargo-generate templates.
+static LIQUID: LazyLock<liquid::Parser> = LazyLock::new(|| {
+    CASES
+        .iter()
+        .fold(
+            liquid::ParserBuilder::with_stdlib(),
+            |builder, &(name, convert)| builder.filter(CaseFilter(name, convert)),
+        )
+        .build()
+        .expect("the filters have distinct names")
+});
+/// Renders `template` as Jinja.
+///
+/// # Errors
+/// If the template is invalid or uses an unknown variable.
+pub(crate) fn render_jinja(template: &str, variables: &Variables) -> Result<String> {
+    Ok(JINJA.render_str(template, variables)?)
+}
+/// Renders `template` as Liquid, like cargo-generate does.
+///
+/// # Errors
+/// If the template is invalid or uses an unknown variable.
+pub(crate) fn render_liquid(template: &str, variables: &Variables) -> Result<String> {
+    let globals = liquid::to_object(variables)?;
+    Ok(LIQUID.parse(template)?.render(&globals)?)
+}
+/// Whether `condition`, a Jinja expression like the one of an `{% if %}`,
+/// holds.
+///
+/// # Errors
+/// If the condition is invalid or uses an unknown variable.
+pub(crate) fn check(condition: &str, variables: &Variables) -> Result<bool> {
+    Ok(JINJA
+        .compile_expression(condition)?
+        .eval(variables)?
+        .is_true())
+}
+#[cfg(test)]
+mod engine_test {
+    use super::{TemplateValue, Variables, check, render_jinja, render_liquid};
+    #[test]
+    fn test_render() {
+        let variables = Variables::from([
+            ("project-name".to_string(), "my-app".into()),
+            ("group".to_string(), "com.example".into()),
+            ("library".to_string(), false.into()),
+            (
+                "features".to_string(),
+                TemplateValue::List(vec!["logs".to_string(), "metrics".to_string()]),
+            ),
+        ]);
+        assert_eq!(
+            render_jinja(
+                "{% if library %}lib{% else %}{{ group | replace('.', '/') }}/{{ features | join('+') | pascal_case }}{% endif %}.\n",
+                &variables
+            )
+            .unwrap(),
+            "com/example/LogsMetrics.\n"
+        );
+        assert!(check("not library and 'logs' in features", &variables).unwrap());
+        assert!(render_jinja("{{ unknown }}", &variables).is_err());
+        assert_eq!(
+            render_liquid(
+                "{{ project-name | snake_case }} {{ group | replace: '.', '/' | upcase }}",
+                &variables
+            )
+            .unwrap(),
+            "my_app COM/EXAMPLE"
+        );
+        assert!(render_liquid("{{ unknown }}", &variables).is_err());
+    }
+}
@@ -0,0 +1,509 @@
+use super::{
+    CargoOptions, CmakeOptions, SpringBootOptions, TemplateOptions,
+    engine::{Variables, render_jinja},
+};
+use crate::{
+    AppContext,
+    common::{Editor, EditorProject, ExecutableMap},
+};
+use anyhow::{Context, Result, bail};
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+use tokio::sync::mpsc;
+/// The templates of the built-in project types, by the path of the file of
+/// `Config::overrides_dir` replacing them.
+pub(crate) const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "cmake/CMakeLists.txt",
+        include_str!("../../templates/cmake/CMakeLists.txt"),
+    ),
+    ("cmake/main.c", include_str!("../../templates/cmake/main.c")),
+    (
+        "cmake/main.cpp",
+        include_str!("../../templates/cmake/main.cpp"),
+    ),
+    (
+        "editor/vscode/settings.json",
+        include_str!("../../templates/editor/vscode/settings.json"),
+    ),
+    (
+        "editor/vscode/extensions.json",
+        include_str!("../../templates/editor/vscode/extensions.json"),
+    ),
+    (
+        "editor/vscode/launch.json",
+        include_str!("../../templates/editor/vscode/launch.json"),
+    ),
+    (
+        "editor/idea/runConfiguration.xml",
+        include_str!("../../templates/editor/idea/runConfiguration.xml"),
+    ),
+    (
+        "editor/neovim/nvim.lua",
+        include_str!("../../templates/editor/neovim/nvim.lua"),
+    ),
+    (
+        "editor/neovim/lazy.lua",
+        include_str!("../../templates/editor/neovim/lazy.lua"),
+    ),
+];
+/// The options of any supported project type.
+#[derive(Debug, Clone)]
+pub enum Options {
+    Cargo(CargoOptions),
+    Cmake(CmakeOptions),
+    SpringBoot(SpringBootOptions),
+    Template(TemplateOptions),
+}
+impl From<CargoOptions> for Options {
+    fn from(options: CargoOptions) -> Self {
+        Self::Cargo(options)
+    }
+}
+impl From<CmakeOptions> for Options {
+    fn from(options: CmakeOptions) -> Self {
+        Self::Cmake(options)
+    }
+}
+impl From<SpringBootOptions> for Options {
+    fn from(options: SpringBootOptions) -> Self {
+        Self::SpringBoot(options)
+    }
+}
+impl From<TemplateOptions> for Options {
+    fn from(options: TemplateOptions) -> Self {
+        Self::Template(options)
+    }
+}
+impl Options {
+    fn name(&self) -> &str {
+        match self {
+            Self::Cargo(options) => &options.name,
+            Self::Cmake(options) => &options.name,
+            Self::SpringBoot(options) => &options.name,
+            Self::Template(options) => &options.name,
+        }
+    }
+}
+/// What `generate` did to create a project.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// The directory the project was created in.
+    pub project_path: PathBuf,
+    /// The file to open first, relative to `project_path`.
+    pub main_file:    String,
+    /// Directories and files written by project-setup itself, the ones
+    /// written by external commands are not listed.
+    pub created:      Vec<PathBuf>,
+    /// External commands and downloads, in the order they were run.
+    pub commands:     Vec<String>,
+    /// The files generated by project-setup itself with their contents, in
+    /// the order they were written.
+    pub contents:     Vec<(PathBuf, String)>,
+    /// Nothing was written nor run, the report only tells what would be.
+    pub dry_run:      bool,
+}
+impl Report {
+    /// Whether `path` is one of the created directories, not a file.
+    pub fn is_dir(&self, path: &Path) -> bool {
+        self.created.iter().any(|created| created == path)
+            && !self.contents.iter().any(|(file, _)| file == path)
+    }
+
+    /// One line per step, like `create <dir>/`, `write <file>` or
+    /// `run <command>`.
+    #[must_use]
+    pub fn actions(&self) -> Vec<String> {
+        self.created
+            .iter()
+            .map(|path| {
+                if self.is_dir(path) {
+                    format!("create {}/", path.display())
+                } else {
+                    format!("write {}", path.display())
+                }
+            })
+            .chain(self.commands.iter().map(|command| format!("run {command}")))
+            .collect()
+    }
+}
+/// Reported while a project is created, for the TUI to follow it.
+pub(crate) enum GenerationEvent {
+    /// A step of `Report::actions` is started.
+    Step(String),
+    /// A line printed by the command being run.
+    Output(String),
+}
+/// Creates the project described by `options` in the directory `target`,
+/// named after the project, e.g. `target/<name>`.
+///
+/// Tools are looked up in `PATH`, without the TUI or its prepare stages.
+///
+/// # Errors
+/// If the options are invalid, a file cannot be written or a command fails.
+pub fn generate(options: impl Into<Options>, target: &Path) -> Result<Report> {
+    let context = AppContext::default();
+    context.set_executables(ExecutableMap::resolve());
+    generate_with(&context, options.into(), target, false)
+}
+/// Tells what `generate` would write and run, without touching anything.
+///
+/// # Errors
+/// If the options are invalid.
+pub fn dry_run(options: impl Into<Options>, target: &Path) -> Result<Report> {
+    let context = AppContext::default();
+    context.set_executables(ExecutableMap::resolve());
+    generate_with(&context, options.into(), target, true)
+}
+/// Same as `generate`, with the executables and caches of `context`.
+pub(crate) fn generate_with(
+    context: &AppContext,
+    options: Options,
+    target: &Path,
+    dry_run: bool,
+) -> Result<Report> {
+    generate_inner(context, options, target, dry_run, None)
+}
+/// Same as `generate_with`, sending every step and output line to `events`.
+pub(crate) fn generate_logged(
+    context: &AppContext,
+    options: Options,
+    target: &Path,
+    events: mpsc::UnboundedSender<GenerationEvent>,
+) -> Result<Report> {
+    generate_inner(context, options, target, false, Some(events))
+}
+fn generate_inner(
+    context: &AppContext,
+    options: Options,
+    target: &Path,
+    dry_run: bool,
+    events: Option<mpsc::UnboundedSender<GenerationEvent>>,
+) -> Result<Report> {
+    if options.name().is_empty() {
+        bail!("name cannot be empty");
+    }
+    match &options {
+        Options::Cmake(options) => options.validate(context.executables())?,
+        Options::SpringBoot(options) => options.validate()?,
+        Options::Cargo(_) | Options::Template(_) => {}
+    }
+    let project_path = target.join(options.name());
+    // 空目录可以直接用, 否则会覆盖已有的项目
+    if project_path.exists()
+        && fs::read_dir(&project_path).map_or(true, |mut entries| entries.next().is_some())
+    {
+        bail!("{} already exists and is not empty", project_path.display());
+    }
+    let mut generation = Generation {
+        context,
+        events,
+        report: Report {
+            project_path,
+            dry_run,
+            ..Report::default()
+        },
+    };
+    generation.create_dir(Path::new(""))?;
+    match &options {
+        Options::Cargo(options) => options.generate(&mut generation)?,
+        Options::Cmake(options) => options.generate(&mut generation)?,
+        Options::SpringBoot(options) => options.generate(&mut generation)?,
+        Options::Template(options) => options.generate(&mut generation)?,
+    }
+    Ok(generation.report)
+}
+/// Carries out the steps of a project type while recording them.
+pub(crate) struct Generation<'a> {
+    pub(crate) context: &'a AppContext,
+    events:             Option<mpsc::UnboundedSender<GenerationEvent>>,
+    report:             Report,
+}
+fn send(events: Option<&mpsc::UnboundedSender<GenerationEvent>>, event: GenerationEvent) {
+    if let Some(events) = events {
+        // Nobody follows the generation anymore once the receiver is gone.
+        let _ = events.send(event);
+    }
+}
+impl Generation<'_> {
+    fn step(&self, step: String) {
+        send(self.events.as_ref(), GenerationEvent::Step(step));
+    }
+
+    pub(crate) fn project_path(&self) -> &Path {
+        &self.report.project_path
+    }
+
+    pub(crate) fn executables(&self) -> &ExecutableMap {
+        self.context.executables()
+    }
+
+    pub(crate) fn dry_run(&self) -> bool {
+        self.report.dry_run
+    }
+
+    pub(crate) fn main_file(&mut self, main_file: impl AsRef<Path>) {
+        self.report.main_file = main_file.as_ref().display().to_string();
+    }
+
+    /// `path` within the project, which rendered paths of templates must not
+    /// leave, neither with `..` nor through a symlink.
+    fn confined(&self, path: &Path) -> Result<PathBuf> {
+        let mut confined = self.report.project_path.clone();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => confined.push(part),
+                Component::CurDir => {}
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    bail!("{} is outside of the project", path.display());
+                }
+            }
+            if fs::symlink_metadata(&confined).is_ok_and(|metadata| metadata.is_symlink()) {
+                bail!(
+                    "{} goes through the symlink {}",
+                    path.display(),
+                    confined.display()
+                );
+            }
+        }
+        Ok(confined)
+    }
+
+    /// Creates `path`, relative to the project, with its parents.
+    pub(crate) fn create_dir(&mut self, path: &Path) -> Result<()> {
+        let path = self.confined(path)?;
+        if !self.dry_run() {
+            self.step(format!("create {}/", path.display()));
+            fs::create_dir_all(&path).with_context(|| format!("creating {}", path.display()))?;
+        }
+        self.report.created.push(path);
+        Ok(())
+    }
+
+    /// Writes `contents` to `path`, relative to the project, creating its
+    /// parents.
+    pub(crate) fn write(
+        &mut self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        let path = self.confined(path.as_ref())?;
+        if !self.dry_run() {
+            self.step(format!("write {}", path.display()));
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, &contents).with_context(|| format!("writing {}", path.display()))?;
+        }
+        self.report.created.push(path.clone());
+        self.report.contents.push((
+            path,
+            String::from_utf8_lossy(contents.as_ref()).into_owned(),
+        ));
+        Ok(())
+    }
+
+    /// Runs `command` to completion, failing with its stderr.
+    pub(crate) fn run(&mut self, command: &mut Command) -> Result<()> {
+        let line = [command.get_program()]
+            .into_iter()
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.dry_run() {
+            self.report.commands.push(line);
+            return Ok(());
+        }
+        self.step(format!("run {line}"));
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to execute {line}"))?;
+        self.report.commands.push(line.clone());
+        // Both pipes are drained at once, so that neither fills up and blocks.
+        let stderr = child.stderr.take().map(|stderr| {
+            let events = self.events.clone();
+            thread::spawn(move || {
+                BufReader::new(stderr)
+                    .lines()
+                    .map_while(Result::ok)
+                    .inspect(|line| send(events.as_ref(), GenerationEvent::Output(line.clone())))
+                    .collect::<Vec<_>>()
+            })
+        });
+        if let Some(stdout) = child.stdout.take() {
+            for output in BufReader::new(stdout).lines().map_while(Result::ok) {
+                send(self.events.as_ref(), GenerationEvent::Output(output));
+            }
+        }
+        let stderr = stderr
+            .and_then(|stderr| stderr.join().ok())
+            .unwrap_or_default();
+        if !child.wait()?.success() {
+            bail!("{line} failed: {}", stderr.join("\n").trim());
+        }
+        Ok(())
+    }
+
+    /// Records a step which is not a plain command, like a download, which
+    /// is only carried out when this is not a dry run.
+    pub(crate) fn record(&mut self, step: String) {
+        if !self.dry_run() {
+            self.step(format!("run {step}"));
+        }
+        self.report.commands.push(step);
+    }
+
+    /// Renders the built-in template `name`, or the file replacing it in the
+    /// overrides directory, and writes it to `path`, which is rendered too.
+    pub(crate) fn write_template(
+        &mut self,
+        path: &str,
+        name: &str,
+        variables: &Variables,
+    ) -> Result<()> {
+        let overridden = self.context.config.overrides_dir.join(name);
+        let template = if overridden.is_file() {
+            fs::read_to_string(&overridden)
+                .with_context(|| format!("reading {}", overridden.display()))?
+        } else {
+            BUILTIN_TEMPLATES
+                .iter()
+                .find(|(builtin, _)| *builtin == name)
+                .map(|(_, template)| (*template).to_string())
+                .with_context(|| format!("no built-in template {name}"))?
+        };
+        let contents = render_jinja(&template, variables)
+            .with_context(|| format!("rendering the template {name}"))?;
+        let path =
+            render_jinja(path, variables).with_context(|| format!("rendering the path {path}"))?;
+        self.write(path, contents)
+    }
+
+    /// Writes the settings of `editor` for `project`, if any.
+    pub(crate) fn editor_settings(
+        &mut self,
+        editor: Option<Editor>,
+        project: &EditorProject,
+    ) -> Result<()> {
+        let variables = project.variables();
+        for (path, name) in editor.map_or(&[][..], Editor::settings_templates) {
+            self.write_template(path, name, &variables)?;
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod generation_test {
+    use super::{CargoOptions, CmakeOptions, Editor, SpringBootOptions, dry_run, generate};
+    use crate::generate::cargo::ProjectType;
+    use std::{env, fs, process};
+    use strum::IntoEnumIterator;
+    #[test]
+    fn test_generate_cmake() {
+        let target = env::temp_dir().join(format!("project-setup-test-{}", process::id()));
+        let report = generate(CmakeOptions::new("hello"), &target).unwrap();
+        let project_path = target.join("hello");
+        assert_eq!(report.project_path, project_path);
+        assert_eq!(report.main_file, "main.c");
+        assert!(report.commands.is_empty());
+        let cmake_lists = fs::read_to_string(project_path.join("CMakeLists.txt")).unwrap();
+        assert!(cmake_lists.contains("project(hello)"));
+        assert!(report.created.contains(&project_path.join("main.c")));
+        for result in [
+            dry_run(CmakeOptions::new("hello"), &target),
+            generate(CmakeOptions::new("hello"), &target),
+        ] {
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("{} already exists and is not empty", project_path.display())
+            );
+        }
+        fs::create_dir(target.join("empty")).unwrap();
+        generate(CmakeOptions::new("empty"), &target).unwrap();
+        fs::remove_dir_all(target).unwrap();
+    }
+    #[test]
+    fn test_dry_run_writes_nothing() {
+        let target = env::temp_dir().join(format!("project-setup-dry-run-{}", process::id()));
+        let report = dry_run(CmakeOptions::new("hello"), &target).unwrap();
+        assert!(!target.exists());
+        assert!(report.dry_run);
+        let (path, cmake_lists) = &report.contents[0];
+        assert_eq!(*path, target.join("hello").join("CMakeLists.txt"));
+        assert!(cmake_lists.contains("project(hello)"));
+    }
+    #[test]
+    fn test_springboot_ids() {
+        let target =
+            env::temp_dir().join(format!("project-setup-springboot-ids-{}", process::id()));
+        for (artifact_id, error) in [
+            ("", "artifact_id cannot be empty"),
+            (
+                "demo.",
+                "artifact_id cannot start or end with '.', nor contain '..'",
+            ),
+            ("de mo", "artifact_id cannot contain ' '"),
+        ] {
+            let mut options = SpringBootOptions::new("hello");
+            options.artifact_id = artifact_id.to_string();
+            assert_eq!(dry_run(options, &target).unwrap_err().to_string(), error);
+        }
+        for (group_id, artifact_id, main_file) in [
+            (
+                "com.example",
+                "été-app",
+                "src/main/java/com/example/été_app/ÉtéAppApplication.java",
+            ),
+            (
+                "org.3d",
+                "class",
+                "src/main/java/org/_3d/class_/ClassApplication.java",
+            ),
+        ] {
+            let mut options = SpringBootOptions::new("hello");
+            options.group_id = group_id.to_string();
+            options.artifact_id = artifact_id.to_string();
+            let report = dry_run(options, &target).unwrap();
+            assert_eq!(report.main_file, main_file);
+            let package = main_file
+                .strip_prefix("src/main/java/")
+                .and_then(|path| path.rsplit_once('/'))
+                .unwrap()
+                .0
+                .replace('/', ".");
+            assert!(report.commands[0].contains(&format!("packageName={package}&")));
+        }
+    }
+    #[test]
+    fn test_editor_settings_templates() {
+        let target =
+            env::temp_dir().join(format!("project-setup-editor-settings-{}", process::id()));
+        for editor in Editor::iter() {
+            let mut cargo = CargoOptions::new("hello");
+            cargo.project_type = ProjectType::Library;
+            cargo.editor_settings = Some(editor);
+            let mut cmake = CmakeOptions::new("hello");
+            cmake.editor_settings = Some(editor);
+            for report in [dry_run(cargo, &target), dry_run(cmake, &target)] {
+                for (path, contents) in report.unwrap().contents {
+                    if path
+                        .extension()
+                        .is_some_and(|extension| extension == "json")
+                    {
+                        serde_json::from_str::<serde_json::Value>(&contents).unwrap();
+                    }
+                    assert!(!contents.contains("{%"), "{}", path.display());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,626 @@
+use super::{
+    TemplateValue, Variables,
+    engine::render_jinja,
+    template::{Manifest, Prompt, PromptKind, Rules, Template, TemplateFormat},
+};
+use crate::{
+    AppContext,
+    common::{ExecutableEnumTrait, ExecutableMap, Vcs, expand_path},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use heck::ToSnakeCase;
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+/// The file declaring the placeholders of a cargo-generate template.
+pub const CARGO_GENERATE: &str = "cargo-generate.toml";
+/// The files declaring the questions of a copier template.
+pub const COPIER: [&str; 2] = ["copier.yml", "copier.yaml"];
+/// Reads the template of `source`, a local directory or a git repository
+/// which is cloned into the cache directory first.
+///
+/// # Errors
+/// If `source` cannot be cloned or is not a template.
+pub fn import(source: &str) -> Result<Template> {
+    let context = AppContext::default();
+    context.set_executables(ExecutableMap::resolve());
+    import_with(&context, source)
+}
+/// Same as `import`, with the executables and the cache directory of
+/// `context`.
+pub(crate) fn import_with(context: &AppContext, source: &str) -> Result<Template> {
+    let local = expand_path(source);
+    // 本地目录直接读取, 其它的都交给 git clone, 包括裸仓库
+    let mut template = if TemplateFormat::detect(&local).is_some() {
+        Template::load(&local)?
+    } else {
+        let git = Vcs::Git.exe();
+        if !context.executables().executable(&git) {
+            bail!("{source} is not a template directory and {git} is not found to clone it");
+        }
+        let checkout = context.config.cache_dir.join("project-setup-imports").join(
+            source
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>(),
+        );
+        if checkout.exists() {
+            fs::remove_dir_all(&checkout)
+                .with_context(|| format!("removing {}", checkout.display()))?;
+        }
+        let output = Command::new(context.executables().path(&git))
+            .args(["clone", "--depth", "1", "--quiet", "--", source])
+            .arg(&checkout)
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("failed to execute {git} clone"))?;
+        if !output.status.success() {
+            bail!(
+                "cloning {source} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Template::load(&checkout)?
+    };
+    template.name = source
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(source)
+        .trim_end_matches(".git")
+        .to_string();
+    Ok(template)
+}
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CargoGenerateConfig {
+    template:     CargoGenerateTemplate,
+    placeholders: toml::Table,
+    conditional:  BTreeMap<String, CargoGenerateConditional>,
+}
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CargoGenerateTemplate {
+    ignore:  Vec<String>,
+    exclude: Vec<String>,
+}
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CargoGenerateConditional {
+    ignore:       Vec<String>,
+    placeholders: toml::Table,
+}
+#[derive(Deserialize)]
+struct Placeholder {
+    #[serde(rename = "type", default)]
+    kind:    String,
+    prompt:  Option<String>,
+    default: Option<toml::Value>,
+    #[serde(default)]
+    choices: Vec<String>,
+    regex:   Option<String>,
+}
+fn placeholder_prompt(name: &str, placeholder: &toml::Value) -> Result<Prompt> {
+    let placeholder: Placeholder = placeholder
+        .clone()
+        .try_into()
+        .map_err(|error| anyhow!("placeholder `{name}`: {}", error.message()))?;
+    let kind = match placeholder.kind.as_str() {
+        "bool" => PromptKind::Bool,
+        _ if !placeholder.choices.is_empty() => PromptKind::Radio,
+        _ => PromptKind::Text,
+    };
+    let mut prompt = Prompt::new(name, kind);
+    prompt.tip = placeholder.prompt;
+    prompt.default = placeholder.default.map(|default| match default {
+        toml::Value::String(text) => text,
+        other => other.to_string(),
+    });
+    prompt.optional = prompt.default.is_some();
+    prompt.empty_is_default = true;
+    prompt.options = placeholder.choices;
+    prompt.validation = placeholder.regex;
+    prompt.compile()?;
+    Ok(prompt)
+}
+/// Reads a cargo-generate template: its placeholders become prompts, its
+/// `ignore` lists and the ones of its conditionals are not copied and the
+/// files of its `exclude` list are copied without being rendered.
+pub(super) fn cargo_generate(path: &Path) -> Result<Template> {
+    let config = fs::read_to_string(path.join(CARGO_GENERATE))
+        .with_context(|| format!("reading {CARGO_GENERATE}"))?;
+    let config: CargoGenerateConfig = toml::from_str(&config)
+        .map_err(|error| anyhow!("invalid {CARGO_GENERATE}: {}", error.message()))?;
+    let mut prompts = vec![];
+    // 条件中的占位符也一并询问, 渲染时才能找到它们
+    for placeholders in [&config.placeholders].into_iter().chain(
+        config
+            .conditional
+            .values()
+            .map(|conditional| &conditional.placeholders),
+    ) {
+        for (name, placeholder) in placeholders {
+            if !prompts.iter().any(|prompt: &Prompt| prompt.name == *name) {
+                prompts.push(placeholder_prompt(name, placeholder)?);
+            }
+        }
+    }
+    let mut ignore = vec![
+        CARGO_GENERATE.to_string(),
+        ".genignore".to_string(),
+        ".git".to_string(),
+    ];
+    ignore.extend(config.template.ignore);
+    if let Ok(genignore) = fs::read_to_string(path.join(".genignore")) {
+        ignore.extend(
+            genignore
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(ToString::to_string),
+        );
+    }
+    Ok(Template {
+        name:      String::new(),
+        path:      path.to_path_buf(),
+        format:    TemplateFormat::CargoGenerate,
+        manifest:  Manifest {
+            description: None,
+            main_file: None,
+            prompts,
+        },
+        variables: BTreeMap::new(),
+        rules:     Rules {
+            ignore,
+            conditional: config
+                .conditional
+                .into_iter()
+                .map(|(condition, conditional)| (condition, conditional.ignore))
+                .collect(),
+            verbatim: config.template.exclude,
+            suffix: Some(".liquid".to_string()),
+            render_all: true,
+        },
+    })
+}
+/// The variables cargo-generate defines for every template.
+pub(super) fn cargo_generate_variables(name: &str, executables: &ExecutableMap) -> Variables {
+    let git_config = |key: &str| {
+        let git = Vcs::Git.exe();
+        executables
+            .executable(&git)
+            .then(|| {
+                Command::new(executables.path(&git))
+                    .args(["config", "--get", key])
+                    .stdin(Stdio::null())
+                    .output()
+                    .ok()
+            })
+            .flatten()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let authors = match (git_config("user.name"), git_config("user.email")) {
+        (Some(name), Some(email)) => format!("{name} <{email}>"),
+        (Some(name), None) => name,
+        (None, _) => String::new(),
+    };
+    [
+        ("project-name", TemplateValue::from(name)),
+        ("crate_name", name.to_snake_case().into()),
+        ("crate_type", "bin".into()),
+        ("authors", authors.into()),
+        (
+            "os-arch",
+            format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH).into(),
+        ),
+        ("is_init", false.into()),
+        ("within_cargo_project", false.into()),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value))
+    .collect()
+}
+/// A yaml scalar as the text it is answered with, lists are joined by
+/// commas.
+fn yaml_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::String(text) => Some(text.clone()),
+        Value::Sequence(values) => Some(
+            values
+                .iter()
+                .filter_map(yaml_text)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Value::Mapping(_) => None,
+        Value::Tagged(tagged) => yaml_text(&tagged.value),
+    }
+}
+/// The values of copier choices, given as a list, a list of
+/// `[label, value]` or a map from labels to values.
+fn copier_choices(choices: &Value) -> Vec<String> {
+    match choices {
+        Value::Sequence(choices) => choices
+            .iter()
+            .filter_map(|choice| match choice {
+                Value::Sequence(pair) => pair.get(1).and_then(yaml_text),
+                choice => yaml_text(choice),
+            })
+            .collect(),
+        Value::Mapping(choices) => choices.values().filter_map(yaml_text).collect(),
+        _ => vec![],
+    }
+}
+/// The prompt of a copier question and whether it is asked, copier skips it
+/// with `when: false`.
+fn copier_prompt(name: &str, question: &Value, defaults: &Variables) -> Result<(Prompt, bool)> {
+    let empty = Mapping::new();
+    let (settings, default) = match question {
+        Value::Mapping(settings) => (settings, settings.get("default")),
+        shorthand => (&empty, Some(shorthand)),
+    };
+    let setting = |key: &str| settings.get(key).and_then(yaml_text);
+    let kind = setting("type").unwrap_or_else(|| {
+        match default {
+            Some(Value::Bool(_)) => "bool",
+            Some(Value::Number(number)) if number.is_f64() => "float",
+            Some(Value::Number(_)) => "int",
+            _ => "str",
+        }
+        .to_string()
+    });
+    let options = settings
+        .get("choices")
+        .map(copier_choices)
+        .unwrap_or_default();
+    let multiselect = settings.get("multiselect") == Some(&Value::Bool(true));
+    let mut prompt = Prompt::new(
+        name,
+        match kind.as_str() {
+            "bool" => PromptKind::Bool,
+            _ if multiselect => PromptKind::List,
+            _ if !options.is_empty() => PromptKind::Radio,
+            _ => PromptKind::Text,
+        },
+    );
+    prompt.tip = setting("help");
+    // 默认值本身也可以是模板, 用前面问题的默认值渲染
+    prompt.default = default
+        .and_then(yaml_text)
+        .map(|default| render_jinja(&default, defaults).unwrap_or(default));
+    prompt.optional = prompt.default.is_some() || kind == "bool";
+    match kind.as_str() {
+        "int" => prompt.validation = Some("-?[0-9]+".to_string()),
+        "float" => prompt.validation = Some("-?[0-9]+(\\.[0-9]+)?".to_string()),
+        _ => {}
+    }
+    if multiselect {
+        prompt.validation = Some(
+            options
+                .iter()
+                .map(|option| regex::escape(option))
+                .collect::<Vec<_>>()
+                .join("|"),
+        );
+        prompt.error = Some(format!("{name} must be among {}", options.join(", ")));
+    } else {
+        prompt.options = options;
+    }
+    prompt.compile()?;
+    let skipped = match settings.get("when") {
+        Some(Value::Bool(when)) => !when,
+        Some(Value::String(when)) => when.trim() == "false",
+        _ => false,
+    };
+    Ok((prompt, !skipped))
+}
+/// Reads a copier template: its questions become prompts, the ones copier
+/// skips keep their defaults, `_exclude` is not copied, only the files
+/// ending with `_templates_suffix` are rendered and `_subdirectory` is the
+/// directory copied.
+pub(super) fn copier(path: &Path) -> Result<Template> {
+    let file = COPIER
+        .iter()
+        .find(|file| path.join(file).is_file())
+        .copied()
+        .unwrap_or(COPIER[0]);
+    let config = fs::read_to_string(path.join(file)).with_context(|| format!("reading {file}"))?;
+    let config: Mapping =
+        serde_yaml::from_str(&config).map_err(|error| anyhow!("invalid {file}: {error}"))?;
+    let mut prompts = vec![];
+    let mut variables = Variables::new();
+    let mut defaults = Variables::new();
+    let (mut subdirectory, mut suffix) = (None, Some(".jinja".to_string()));
+    // copier 默认不复制的文件
+    let mut ignore: Vec<String> = ["~*", "*.py[co]", "__pycache__", ".DS_Store", ".svn"]
+        .map(ToString::to_string)
+        .to_vec();
+    for (key, value) in &config {
+        let key = yaml_text(key).unwrap_or_default();
+        match key.as_str() {
+            "_subdirectory" => subdirectory = yaml_text(value),
+            "_templates_suffix" => suffix = yaml_text(value).filter(|suffix| !suffix.is_empty()),
+            // 替换掉默认的列表, 和 copier 一样
+            "_exclude" => {
+                ignore = match value {
+                    Value::Sequence(patterns) => patterns.iter().filter_map(yaml_text).collect(),
+                    _ => bail!("_exclude of {file} is not a list"),
+                };
+            }
+            // 其它设置, 比如 _tasks, 不支持
+            _ if key.starts_with('_') => {}
+            _ => {
+                let (prompt, asked) = copier_prompt(&key, value, &defaults)
+                    .with_context(|| format!("question `{key}`"))?;
+                let default = prompt.value(prompt.default.as_deref().unwrap_or_default());
+                defaults.insert(key.clone(), default.clone());
+                if asked {
+                    prompts.push(prompt);
+                } else {
+                    variables.insert(key, default);
+                }
+            }
+        }
+    }
+    // 克隆下来的仓库和 copier 自己的文件, 不论 _exclude 如何都不复制
+    ignore.extend(
+        [".git", "*_copier_conf*"]
+            .into_iter()
+            .chain(COPIER)
+            .map(ToString::to_string),
+    );
+    Ok(Template {
+        name: String::new(),
+        path: subdirectory.map_or_else(
+            || path.to_path_buf(),
+            |subdirectory| path.join(subdirectory),
+        ),
+        format: TemplateFormat::Copier,
+        manifest: Manifest {
+            description: None,
+            main_file: None,
+            prompts,
+        },
+        variables,
+        rules: Rules {
+            render_all: suffix.is_none(),
+            ignore,
+            suffix,
+            ..Rules::default()
+        },
+    })
+}
+#[cfg(test)]
+mod import_test {
+    use super::{COPIER, import};
+    use crate::generate::{TemplateOptions, TemplateValue, generate, template::PromptKind};
+    use std::{env, fs, path::Path, process::Command};
+    /// Commits `files` into a new bare repository at `bare`.
+    fn bare_repository(root: &Path, bare: &Path, files: &[(&str, &str)]) {
+        let work = root.join("work");
+        for (file, contents) in files {
+            let path = work.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        for args in [
+            &["init", "--quiet"][..],
+            &["add", "."],
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "template",
+            ],
+        ] {
+            assert!(
+                Command::new("git")
+                    .args(args)
+                    .current_dir(&work)
+                    .status()
+                    .unwrap()
+                    .success()
+            );
+        }
+        assert!(
+            Command::new("git")
+                .args(["clone", "--bare", "--quiet"])
+                .arg(&work)
+                .arg(bare)
+                .status()
+                .unwrap()
+                .success()
+        );
+        fs::remove_dir_all(work).unwrap();
+    }
+    #[test]
+    fn test_import_cargo_generate() {
+        let root = env::temp_dir().join(format!(
+            "project-setup-cargo-generate-{}",
+            std::process::id()
+        ));
+        let bare = root.join("rust-starter.git");
+        bare_repository(
+            &root,
+            &bare,
+            &[
+                (
+                    "cargo-generate.toml",
+                    r#"
+                        [template]
+                        ignore = ["notes.md"]
+                        exclude = ["assets/*"]
+                        [placeholders]
+                        license = { type = "string", prompt = "License?", choices = ["MIT", "Apache-2.0"], default = "MIT" }
+                        serde = { type = "bool", prompt = "Use serde?", default = false }
+                        year = { type = "string", prompt = "Year?", default = "2024" }
+                        [conditional.'serde == false']
+                        ignore = ["src/model.rs"]
+                    "#,
+                ),
+                (
+                    "Cargo.toml.liquid",
+                    "[package]\nname = \"{{ project-name }}\"\nlicense = \"{{ license }}\"\n",
+                ),
+                (
+                    "src/main.rs",
+                    "// {{ crate_name }} {{ year }}\nfn main() {}\n",
+                ),
+                ("src/model.rs", "// serde\n"),
+                ("assets/logo.txt", "{{ not rendered }}"),
+                ("notes.md", "ignored"),
+            ],
+        );
+        let template = import(&bare.display().to_string()).unwrap();
+        assert_eq!(template.name, "rust-starter");
+        let prompts = &template.manifest.prompts;
+        assert_eq!(prompts[0].name, "license");
+        assert_eq!(prompts[0].kind, PromptKind::Radio);
+        assert_eq!(prompts[1].kind, PromptKind::Bool);
+        // 清空的回答用默认值
+        let year = prompts[2].value("");
+        assert_eq!(year, TemplateValue::Text("2024".to_string()));
+        assert_eq!(prompts[2].validate(""), "");
+        let mut options = TemplateOptions::new("my-tool", template);
+        options.values.insert("year".to_string(), year);
+        generate(options, &root.join("out")).unwrap();
+        let project = root.join("out").join("my-tool");
+        assert_eq!(
+            fs::read_to_string(project.join("Cargo.toml")).unwrap(),
+            "[package]\nname = \"my-tool\"\nlicense = \"MIT\"\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join("src/main.rs")).unwrap(),
+            "// my_tool 2024\nfn main() {}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join("assets/logo.txt")).unwrap(),
+            "{{ not rendered }}"
+        );
+        for ignored in ["src/model.rs", "notes.md", "cargo-generate.toml", ".git"] {
+            assert!(!project.join(ignored).exists(), "{ignored}");
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+    #[test]
+    fn test_import_copier() {
+        let root = env::temp_dir().join(format!("project-setup-copier-{}", std::process::id()));
+        let bare = root.join("py-starter.git");
+        bare_repository(
+            &root,
+            &bare,
+            &[
+                (
+                    COPIER[0],
+                    r#"
+                        _subdirectory: template
+                        _exclude: ["*.bak"]
+                        package:
+                          type: str
+                          help: The python package
+                          default: "{{ 'demo' | upper }}"
+                        docker: false
+                        port:
+                          type: int
+                          default: 8000
+                        ci:
+                          choices: [github, gitlab]
+                          when: false
+                          default: gitlab
+                    "#,
+                ),
+                (
+                    "template/{{ package | lower }}/__init__.py.jinja",
+                    "PORT = {{ port }}\n",
+                ),
+                (
+                    "template/{% if docker %}Dockerfile{% endif %}.jinja",
+                    "FROM python\n",
+                ),
+                ("template/README.md", "{{ package }} is not rendered\n"),
+                ("template/ci.txt.jinja", "{{ ci }}\n"),
+                ("template/old.bak", "excluded"),
+            ],
+        );
+        let template = import(&format!("file://{}", bare.display())).unwrap();
+        assert_eq!(template.name, "py-starter");
+        let names: Vec<_> = template
+            .manifest
+            .prompts
+            .iter()
+            .map(|prompt| prompt.name.as_str())
+            .collect();
+        assert_eq!(names, ["package", "docker", "port"]);
+        assert_eq!(
+            template.manifest.prompts[2].validate("80a"),
+            "port must match -?[0-9]+"
+        );
+        let mut options = TemplateOptions::new("service", template);
+        assert_eq!(
+            options.values["package"],
+            TemplateValue::Text("DEMO".to_string())
+        );
+        options
+            .values
+            .insert("docker".to_string(), TemplateValue::Bool(true));
+        generate(options, &root.join("out")).unwrap();
+        let project = root.join("out").join("service");
+        assert_eq!(
+            fs::read_to_string(project.join("demo/__init__.py")).unwrap(),
+            "PORT = 8000\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join("Dockerfile")).unwrap(),
+            "FROM python\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join("README.md")).unwrap(),
+            "{{ package }} is not rendered\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join("ci.txt")).unwrap(),
+            "gitlab\n"
+        );
+        assert!(!project.join("old.bak").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+    #[test]
+    fn test_import_copier_exclude() {
+        let root = env::temp_dir().join(format!(
+            "project-setup-copier-exclude-{}",
+            std::process::id()
+        ));
+        let bare = root.join("flat.git");
+        bare_repository(
+            &root,
+            &bare,
+            &[
+                (COPIER[0], "_exclude: [\"*.bak\"]\nname_upper: NAME\n"),
+                ("README.md", "flat\n"),
+                ("old.bak", "excluded"),
+            ],
+        );
+        let template = import(&bare.display().to_string()).unwrap();
+        generate(TemplateOptions::new("flat", template), &root.join("out")).unwrap();
+        let project = root.join("out").join("flat");
+        assert!(project.join("README.md").exists());
+        // _exclude 替换了默认列表, 但 .git 和 copier.yml 总是不复制
+        for excluded in ["old.bak", ".git", COPIER[0]] {
+            assert!(!project.join(excluded).exists(), "{excluded}");
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,282 @@
+use super::{Generation, RadioOptionValue};
+use crate::{
+    AppContext, EnumFunc, LoopableNumberedEnum, RadioOption,
+    common::{Editor, EditorProject, Requirement, Vcs},
+    features::{RequestMethod, download_file, unzip},
+};
+use anyhow::{Result, bail};
+use heck::ToUpperCamelCase;
+use std::{
+    fs, process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use strum_macros::{Display, EnumIter};
+#[derive(
+    Clone, Copy, Default, Display, Debug, LoopableNumberedEnum, EnumIter, PartialEq, RadioOption,
+)]
+pub enum Generator {
+    #[default]
+    Maven,
+    Gradle,
+}
+#[cfg(test)]
+const _: () = {
+    assert!(matches!(Generator::Gradle.next(), Generator::Maven));
+    assert!(matches!(Generator::Maven.prev(), Generator::Gradle));
+};
+#[derive(Clone, Copy, Default, Display, Debug, EnumIter, PartialEq, RadioOption, EnumFunc)]
+pub enum Language {
+    #[default]
+    #[enum_func(extension("java"))]
+    Java,
+    #[enum_func(extension("kt"))]
+    Kotlin,
+}
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, EnumFunc)]
+pub enum JavaVersion {
+    #[strum(to_string = "25")]
+    #[enum_func(requires("java 25"))]
+    TwentyFive,
+    #[strum(to_string = "21")]
+    #[enum_func(requires("java 21"))]
+    TwentyOne,
+    #[default]
+    #[strum(to_string = "17")]
+    #[enum_func(requires("java 17"))]
+    Seventeen,
+    #[strum(to_string = "8")]
+    #[enum_func(requires("java 1.8"))]
+    Eight,
+}
+impl RadioOptionValue for JavaVersion {
+    fn selectable(&self, context: &AppContext) -> bool {
+        self.unmet_requirement(context).is_none()
+    }
+
+    fn unmet_requirement(&self, context: &AppContext) -> Option<String> {
+        Requirement::new(self.requires()).unmet(context.executables())
+    }
+}
+/// Words which cannot name a package nor a class.
+const JAVA_KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+/// `id` turned into a Java identifier, like start.spring.io does for the
+/// package name: `-` and other symbols become `_`, and a leading digit or a
+/// keyword is escaped with `_`.
+fn java_identifier(id: &str) -> String {
+    let mut identifier: String = id
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    if JAVA_KEYWORDS.contains(&identifier.as_str()) {
+        identifier.push('_');
+    }
+    identifier
+}
+/// A SpringBoot project, downloaded from start.spring.io.
+#[derive(Debug, Clone)]
+pub struct SpringBootOptions {
+    pub name:            String,
+    pub generator:       Generator,
+    pub group_id:        String,
+    pub artifact_id:     String,
+    pub boot_version:    String,
+    pub language:        Language,
+    pub java_version:    JavaVersion,
+    pub dependencies:    Vec<String>,
+    pub vcs:             Vcs,
+    /// The editor to write debug and run settings for.
+    pub editor_settings: Option<Editor>,
+}
+impl SpringBootOptions {
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name:            name.into(),
+            generator:       Generator::default(),
+            group_id:        "com.example".to_string(),
+            artifact_id:     "demo".to_string(),
+            boot_version:    "3.3.0".to_string(),
+            language:        Language::default(),
+            java_version:    JavaVersion::default(),
+            dependencies:    vec![],
+            vcs:             Vcs::default(),
+            editor_settings: None,
+        }
+    }
+
+    /// The package of the application class, sent as `packageName` so that
+    /// the downloaded project has it too.
+    fn package(&self) -> String {
+        self.group_id
+            .split('.')
+            .chain([self.artifact_id.as_str()])
+            .map(java_identifier)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Sent as `applicationName`, start.spring.io would derive it from `name`
+    /// otherwise.
+    fn application_class(&self) -> String {
+        java_identifier(&(self.artifact_id.to_upper_camel_case() + "Application"))
+    }
+
+    /// Checks the ids the package and the application class are made of.
+    pub(super) fn validate(&self) -> Result<()> {
+        for (label, id) in [
+            ("group_id", &self.group_id),
+            ("artifact_id", &self.artifact_id),
+        ] {
+            if id.is_empty() {
+                bail!("{label} cannot be empty");
+            }
+            if id.split('.').any(str::is_empty) {
+                bail!("{label} cannot start or end with '.', nor contain '..'");
+            }
+            if let Some(c) = id
+                .chars()
+                .find(|c| !(c.is_alphanumeric() || matches!(c, '.' | '_' | '-')))
+            {
+                bail!("{label} cannot contain {c:?}");
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn generate(&self, generation: &mut Generation) -> Result<()> {
+        if let Some(mut command) = self
+            .vcs
+            .init_command(generation.executables(), generation.project_path())
+        {
+            generation.run(&mut command)?;
+        }
+        let params = [
+            ("groupId", self.group_id.clone()),
+            ("artifactId", self.artifact_id.clone()),
+            (
+                "type",
+                format!("{}-project", self.generator.to_string().to_lowercase()),
+            ),
+            ("name", self.name.clone()),
+            ("packageName", self.package()),
+            ("applicationName", self.application_class()),
+            ("language", self.language.to_string().to_lowercase()),
+            ("javaVersion", self.java_version.to_string()),
+            ("bootVersion", self.boot_version.clone()),
+            ("baseDir", self.name.clone()),
+            ("dependencies", self.dependencies.join(",")),
+        ];
+        let url = "https://start.spring.io/starter.zip";
+        // 同时创建多个项目时, 各自下载到不同的文件
+        static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+        let temp_zip_file = generation.context.config.cache_dir.join(format!(
+            "starter-{}-{}.zip",
+            process::id(),
+            DOWNLOADS.fetch_add(1, Ordering::Relaxed)
+        ));
+        // `baseDir` already names the project, so it is unzipped next to it.
+        let target = generation
+            .project_path()
+            .parent()
+            .map(ToOwned::to_owned)
+            .unwrap_or_default();
+        generation.record(format!(
+            "{} {url} {}",
+            RequestMethod::POST,
+            params
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>()
+                .join("&")
+        ));
+        generation.record(format!(
+            "unzip {} into {}",
+            temp_zip_file.display(),
+            target.display()
+        ));
+        if !generation.dry_run() {
+            let downloaded = download_file(
+                generation.executables(),
+                url,
+                &RequestMethod::POST,
+                &params,
+                &temp_zip_file,
+            )
+            .and_then(|()| unzip(generation.executables(), &temp_zip_file, &target));
+            let _ = fs::remove_file(&temp_zip_file);
+            downloaded?;
+        }
+        generation.editor_settings(
+            self.editor_settings,
+            &EditorProject::SpringBoot {
+                name:       self.name.clone(),
+                main_class: format!("{}.{}", self.package(), self.application_class()),
+            },
+        )?;
+        generation.main_file(format!(
+            "src/main/{}/{}/{}.{}",
+            self.language.to_string().to_lowercase(),
+            self.package().replace('.', "/"),
+            self.application_class(),
+            self.language.extension()
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,578 @@
+use super::{
+    Generation, TemplateValue, Variables,
+    engine::{check, render_jinja, render_liquid},
+    import,
+};
+use crate::common::Vcs;
+use anyhow::{Context, Result, anyhow, bail};
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+/// The file of a template directory declaring its prompts, it is not copied.
+pub const MANIFEST: &str = "template.toml";
+/// How a prompt is answered in the form.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptKind {
+    #[default]
+    Text,
+    Radio,
+    /// Comma separated values.
+    List,
+    /// `true` or `false`, chosen like a radio.
+    Bool,
+}
+/// A value asked for before rendering a template.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Prompt {
+    /// The variable the answer is rendered as.
+    pub name:                    String,
+    #[serde(default)]
+    pub kind:                    PromptKind,
+    pub label:                   Option<String>,
+    pub tip:                     Option<String>,
+    pub default:                 Option<String>,
+    /// The values a radio chooses from.
+    #[serde(default)]
+    pub options:                 Vec<String>,
+    /// A regular expression the answer, or every value of a list, has to
+    /// match as a whole.
+    pub validation:              Option<String>,
+    /// Shown when the validation fails.
+    pub error:                   Option<String>,
+    /// An empty answer is accepted.
+    #[serde(default)]
+    pub optional:                bool,
+    /// An empty answer stands for the default, like in cargo-generate.
+    #[serde(skip)]
+    pub(crate) empty_is_default: bool,
+    #[serde(skip)]
+    regex:                       Option<Regex>,
+}
+impl Prompt {
+    pub(crate) fn new(name: impl Into<String>, kind: PromptKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            label: None,
+            tip: None,
+            default: None,
+            options: vec![],
+            validation: None,
+            error: None,
+            optional: false,
+            empty_is_default: false,
+            regex: None,
+        }
+    }
+
+    /// Checks the prompt and compiles its validation.
+    pub(crate) fn compile(&mut self) -> Result<()> {
+        if self.kind == PromptKind::Radio && self.options.is_empty() {
+            bail!("radio prompt `{}` has no options", self.name);
+        }
+        if let Some(validation) = &self.validation {
+            self.regex = Some(
+                Regex::new(&format!("^(?:{validation})$"))
+                    .with_context(|| format!("validation of prompt `{}`", self.name))?,
+            );
+        }
+        Ok(())
+    }
+
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    #[must_use]
+    pub fn tip(&self) -> String {
+        self.tip.clone().unwrap_or_else(|| match self.kind {
+            PromptKind::Text => format!("Please input the {} of this project", self.label()),
+            PromptKind::Radio | PromptKind::Bool => {
+                format!("Use arrow keys to select {}", self.label())
+            }
+            PromptKind::List => format!(
+                "Please input the {} of this project, separated by commas",
+                self.label()
+            ),
+        })
+    }
+
+    /// An error message unless `answer` is valid.
+    #[must_use]
+    pub fn validate(&self, answer: &str) -> String {
+        let invalid = |value: &str| {
+            self.regex
+                .as_ref()
+                .is_some_and(|regex| !regex.is_match(value))
+        };
+        let invalid = match self.value(answer) {
+            TemplateValue::Text(value) if value.is_empty() => {
+                if !self.optional {
+                    return format!("{} cannot be empty", self.label());
+                }
+                false
+            }
+            TemplateValue::Text(value) => invalid(&value),
+            TemplateValue::List(values) => values.iter().any(|value| invalid(value)),
+            TemplateValue::Bool(_) => false,
+        };
+        if !invalid {
+            return String::new();
+        }
+        self.error.clone().unwrap_or_else(|| {
+            format!(
+                "{} must match {}",
+                self.label(),
+                self.validation.as_deref().unwrap_or_default()
+            )
+        })
+    }
+
+    /// The value `answer` is rendered as, lists are split at commas.
+    #[must_use]
+    pub fn value(&self, answer: &str) -> TemplateValue {
+        let answer = match &self.default {
+            Some(default) if self.empty_is_default && answer.trim().is_empty() => default,
+            _ => answer,
+        };
+        match self.kind {
+            PromptKind::List => TemplateValue::List(
+                answer
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(ToString::to_string)
+                    .collect(),
+            ),
+            PromptKind::Bool => TemplateValue::Bool(answer == "true"),
+            PromptKind::Text | PromptKind::Radio => TemplateValue::Text(answer.to_string()),
+        }
+    }
+}
+/// The `template.toml` of a template.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub description: Option<String>,
+    /// The file to open first, rendered like the paths of the template.
+    pub main_file:   Option<String>,
+    #[serde(default)]
+    pub prompts:     Vec<Prompt>,
+}
+/// The tool a template was written for, told by the file declaring its
+/// prompts.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TemplateFormat {
+    /// A `template.toml`.
+    #[default]
+    Manifest,
+    /// A `cargo-generate.toml`, rendered like cargo-generate does.
+    CargoGenerate,
+    /// A `copier.yml`, rendered like copier does.
+    Copier,
+}
+impl TemplateFormat {
+    /// Renders `template` in the syntax of the tool, Liquid for
+    /// cargo-generate and Jinja otherwise.
+    fn render(self, template: &str, variables: &Variables) -> Result<String> {
+        match self {
+            Self::CargoGenerate => render_liquid(template, variables),
+            Self::Manifest | Self::Copier => render_jinja(template, variables),
+        }
+    }
+
+    pub(super) fn detect(path: &Path) -> Option<Self> {
+        if path.join(MANIFEST).is_file() {
+            Some(Self::Manifest)
+        } else if path.join(import::CARGO_GENERATE).is_file() {
+            Some(Self::CargoGenerate)
+        } else if import::COPIER.iter().any(|file| path.join(file).is_file()) {
+            Some(Self::Copier)
+        } else {
+            None
+        }
+    }
+}
+/// Which files of a template are copied and which of them are rendered.
+///
+/// Patterns are globs matched against the path in the template, a pattern
+/// without `/` matches names at any depth.
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    /// The files and directories which are not copied.
+    pub ignore:      Vec<String>,
+    /// Patterns only ignored when their condition, like
+    /// `database == "none"`, holds.
+    pub conditional: Vec<(String, Vec<String>)>,
+    /// The files copied without being rendered.
+    pub verbatim:    Vec<String>,
+    /// Removed from the names of the files ending with it, which are
+    /// rendered, like `.jinja`.
+    pub suffix:      Option<String>,
+    /// Whether the files without `suffix` are rendered too.
+    pub render_all:  bool,
+}
+impl Rules {
+    /// The patterns of the files which are not copied with `variables`.
+    fn ignored(&self, variables: &Variables) -> Result<Vec<Pattern>> {
+        let mut ignore = self.ignore.clone();
+        for (condition, patterns) in &self.conditional {
+            if check(condition, variables).with_context(|| format!("condition `{condition}`"))? {
+                ignore.extend(patterns.iter().cloned());
+            }
+        }
+        compile_patterns(&ignore)
+    }
+}
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            let pattern = pattern.trim_start_matches("./").trim_matches('/');
+            Pattern::new(pattern).with_context(|| format!("invalid pattern `{pattern}`"))
+        })
+        .collect()
+}
+fn matches(patterns: &[Pattern], relative: &Path) -> bool {
+    const OPTIONS: MatchOptions = MatchOptions {
+        case_sensitive:              true,
+        require_literal_separator:   true,
+        require_literal_leading_dot: false,
+    };
+    let name = relative.file_name().unwrap_or_default().to_string_lossy();
+    patterns.iter().any(|pattern| {
+        pattern.matches_path_with(relative, OPTIONS)
+            || (!pattern.as_str().contains('/') && pattern.matches_with(&name, OPTIONS))
+    })
+}
+/// A directory of files rendered into new projects.
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// The name of the directory.
+    pub name:      String,
+    /// The directory the files are copied from.
+    pub path:      PathBuf,
+    pub format:    TemplateFormat,
+    pub manifest:  Manifest,
+    /// The values of the variables which are not asked, like the questions
+    /// copier is told to skip.
+    pub variables: BTreeMap<String, TemplateValue>,
+    pub rules:     Rules,
+}
+impl Template {
+    /// Reads the template in `path`, declared by a `template.toml`, a
+    /// `cargo-generate.toml` or a `copier.yml`, and checks its prompts.
+    ///
+    /// # Errors
+    /// If there is no such file, it cannot be read or it declares invalid
+    /// prompts.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut template = match TemplateFormat::detect(path) {
+            Some(TemplateFormat::Manifest) => Self::load_manifest(path)?,
+            Some(TemplateFormat::CargoGenerate) => import::cargo_generate(path)?,
+            Some(TemplateFormat::Copier) => import::copier(path)?,
+            None => bail!(
+                "no {MANIFEST}, {} or {} found",
+                import::CARGO_GENERATE,
+                import::COPIER[0]
+            ),
+        };
+        template.name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        Ok(template)
+    }
+
+    fn load_manifest(path: &Path) -> Result<Self> {
+        let manifest_path = path.join(MANIFEST);
+        let manifest =
+            fs::read_to_string(&manifest_path).with_context(|| format!("reading {MANIFEST}"))?;
+        // 错误只显示在一行里, 不要多行的源码标注
+        let mut manifest: Manifest = toml::from_str(&manifest)
+            .map_err(|error| anyhow!("invalid {MANIFEST}: {}", error.message()))?;
+        let mut names = vec!["name".to_string()];
+        for prompt in &mut manifest.prompts {
+            if names.contains(&prompt.name) {
+                bail!("prompt `{}` is declared twice or reserved", prompt.name);
+            }
+            names.push(prompt.name.clone());
+            prompt.compile()?;
+        }
+        Ok(Self {
+            name: String::new(),
+            path: path.to_path_buf(),
+            format: TemplateFormat::Manifest,
+            manifest,
+            variables: BTreeMap::new(),
+            rules: Rules {
+                ignore: vec![MANIFEST.to_string(), ".git".to_string()],
+                render_all: true,
+                ..Rules::default()
+            },
+        })
+    }
+}
+/// The templates of a directory, cached once `TemplateInner` is prepared.
+#[derive(Debug, Default)]
+pub(crate) struct Templates {
+    pub(crate) found:  Vec<Template>,
+    /// Why the other directories are not templates.
+    pub(crate) errors: Vec<String>,
+}
+impl Templates {
+    /// Every directory of `dir` with a manifest, sorted by name. A missing
+    /// `dir` has no templates.
+    pub(crate) fn load(dir: &Path) -> Self {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir())
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        let mut templates = Self::default();
+        for path in paths {
+            match Template::load(&path) {
+                Ok(template) => templates.found.push(template),
+                Err(error) => templates.errors.push(format!(
+                    "{}: {error:#}",
+                    path.file_name().unwrap_or_default().display()
+                )),
+            }
+        }
+        templates
+    }
+}
+/// A project rendered from a template directory.
+#[derive(Debug, Clone)]
+pub struct TemplateOptions {
+    pub name:     String,
+    pub template: Template,
+    /// The value of every prompt, by name.
+    pub values:   BTreeMap<String, TemplateValue>,
+    pub vcs:      Vcs,
+}
+impl TemplateOptions {
+    #[must_use]
+    pub fn new(name: impl Into<String>, template: Template) -> Self {
+        let values = template
+            .manifest
+            .prompts
+            .iter()
+            .map(|prompt| {
+                let default = match prompt.kind {
+                    PromptKind::Radio => prompt
+                        .default
+                        .as_deref()
+                        .or(prompt.options.first().map(String::as_str)),
+                    PromptKind::Bool => Some(prompt.default.as_deref().unwrap_or("false")),
+                    PromptKind::Text | PromptKind::List => prompt.default.as_deref(),
+                };
+                (
+                    prompt.name.clone(),
+                    prompt.value(default.unwrap_or_default()),
+                )
+            })
+            .collect();
+        Self {
+            name: name.into(),
+            template,
+            values,
+            vcs: Vcs::default(),
+        }
+    }
+
+    pub(super) fn generate(&self, generation: &mut Generation) -> Result<()> {
+        if let Some(mut command) = self
+            .vcs
+            .init_command(generation.executables(), generation.project_path())
+        {
+            generation.run(&mut command)?;
+        }
+        let mut variables = self.template.variables.clone();
+        variables.extend(self.values.clone());
+        if self.template.format == TemplateFormat::CargoGenerate {
+            variables.extend(import::cargo_generate_variables(
+                &self.name,
+                generation.executables(),
+            ));
+        }
+        variables
+            .entry("name".to_string())
+            .or_insert_with(|| TemplateValue::Text(self.name.clone()));
+        let (rules, format) = (&self.template.rules, self.template.format);
+        let ignored = rules.ignored(&variables)?;
+        let verbatim = compile_patterns(&rules.verbatim)?;
+        // 模板中的相对路径, 以及渲染后在项目中的路径
+        let mut dirs = vec![(PathBuf::new(), PathBuf::new())];
+        while let Some((dir, target_dir)) = dirs.pop() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(self.template.path.join(&dir))
+                .with_context(|| format!("reading the template {}", self.template.name))?
+                .map(|entry| entry.map(|entry| dir.join(entry.file_name())))
+                .collect::<Result<_, _>>()?;
+            entries.sort();
+            // 逆序入栈, 使子目录按名字顺序展开
+            for relative in entries.into_iter().rev() {
+                if matches(&ignored, &relative) {
+                    continue;
+                }
+                let source = self.template.path.join(&relative);
+                // 符号链接可能指向模板之外, 不跟随
+                if fs::symlink_metadata(&source)?.is_symlink() {
+                    continue;
+                }
+                let file_name = relative.file_name().unwrap_or_default().to_string_lossy();
+                // 带后缀的文件去掉后缀后渲染
+                let (file_name, suffixed) = match rules
+                    .suffix
+                    .as_deref()
+                    .and_then(|suffix| file_name.strip_suffix(suffix))
+                {
+                    Some(stem) if !source.is_dir() => (stem, true),
+                    _ => (&*file_name, false),
+                };
+                let name = format
+                    .render(file_name, &variables)
+                    .with_context(|| format!("rendering the path {}", relative.display()))?;
+                // 名字渲染为空的文件或目录不生成
+                if name.trim().is_empty() {
+                    continue;
+                }
+                let target = target_dir.join(name);
+                if source.is_dir() {
+                    generation.create_dir(&target)?;
+                    dirs.push((relative, target));
+                    continue;
+                }
+                let contents =
+                    fs::read(&source).with_context(|| format!("reading {}", source.display()))?;
+                let rendered = (suffixed || rules.render_all) && !matches(&verbatim, &relative);
+                match String::from_utf8(contents) {
+                    Ok(text) if rendered => {
+                        let text = format
+                            .render(&text, &variables)
+                            .with_context(|| format!("rendering {}", relative.display()))?;
+                        generation.write(&target, text)?;
+                    }
+                    Ok(text) => generation.write(&target, text)?,
+                    // 二进制文件原样复制
+                    Err(binary) => generation.write(&target, binary.into_bytes())?,
+                }
+            }
+        }
+        if let Some(main_file) = &self.template.manifest.main_file {
+            generation.main_file(
+                format
+                    .render(main_file, &variables)
+                    .context("rendering the main file")?,
+            );
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod template_test {
+    use super::{MANIFEST, TemplateOptions, TemplateValue, Templates};
+    use crate::generate::generate;
+    use std::{env, fs, process};
+    #[test]
+    fn test_render_template() {
+        let root = env::temp_dir().join(format!("project-setup-template-{}", process::id()));
+        let template = root.join("templates").join("service");
+        fs::create_dir_all(template.join("src").join("{{ package }}")).unwrap();
+        fs::write(
+            template.join(MANIFEST),
+            r#"
+                main_file = "src/{{ package }}/main.txt"
+                [[prompts]]
+                name = "package"
+                validation = "[a-z]+"
+                [[prompts]]
+                name = "features"
+                kind = "list"
+                default = "logs, metrics"
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            template.join("src").join("{{ package }}").join("main.txt"),
+            "{{ name | pascal_case }} in {{package}} with {{ features | join(', ') }}\n\
+             {% for feature in features %}\n\
+             - {{ feature | upper }}\n\
+             {% endfor %}\n",
+        )
+        .unwrap();
+        fs::write(
+            template.join("{% if 'docker' in features %}Dockerfile{% endif %}"),
+            "FROM scratch\n",
+        )
+        .unwrap();
+        fs::write(root.join("secret.txt"), "outside of the template\n").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.join("secret.txt"), template.join("secret.txt")).unwrap();
+        fs::create_dir_all(root.join("templates").join("broken")).unwrap();
+        let templates = Templates::load(&root.join("templates"));
+        assert_eq!(templates.found.len(), 1);
+        assert!(templates.errors[0].starts_with("broken: "));
+        let service = templates.found[0].clone();
+        let package = &service.manifest.prompts[0];
+        assert!(package.validate("demo").is_empty());
+        assert_eq!(package.validate("Demo"), "package must match [a-z]+");
+        let mut options = TemplateOptions::new("hello-world", service);
+        assert_eq!(
+            options.values["features"],
+            TemplateValue::List(vec!["logs".to_string(), "metrics".to_string()])
+        );
+        options.values.insert(
+            "package".to_string(),
+            TemplateValue::Text("demo".to_string()),
+        );
+        let report = generate(options, &root.join("out")).unwrap();
+        assert_eq!(report.main_file, "src/demo/main.txt");
+        assert_eq!(
+            fs::read_to_string(root.join("out/hello-world/src/demo/main.txt")).unwrap(),
+            "HelloWorld in demo with logs, metrics\n- LOGS\n- METRICS\n"
+        );
+        assert!(!root.join("out/hello-world").join(MANIFEST).exists());
+        assert!(!root.join("out/hello-world/Dockerfile").exists());
+        assert!(!root.join("out/hello-world/secret.txt").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+    #[test]
+    fn test_rendered_paths_stay_in_project() {
+        let root = env::temp_dir().join(format!("project-setup-escape-{}", process::id()));
+        let template = root.join("templates").join("escape");
+        fs::create_dir_all(&template).unwrap();
+        fs::write(
+            template.join(MANIFEST),
+            "[[prompts]]\nname = \"dir\"\ndefault = \"../../escaped\"\n",
+        )
+        .unwrap();
+        fs::write(template.join("{{ dir }}.txt"), "escaped\n").unwrap();
+        let escape = Templates::load(&root.join("templates")).found[0].clone();
+        let out = root.join("out");
+        let error = generate(TemplateOptions::new("proj", escape.clone()), &out).unwrap_err();
+        assert!(format!("{error:#}").contains("outside of the project"));
+        assert!(!root.join("escaped.txt").exists());
+        let mut options = TemplateOptions::new("proj", escape);
+        let absolute = root.join("absolute").display().to_string();
+        options
+            .values
+            .insert("dir".to_string(), TemplateValue::Text(absolute));
+        assert!(generate(options, &out).is_err());
+        assert!(!root.join("absolute.txt").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    AppContext,
+    common::{Executable, ProjectType, Version, print_table},
+};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+/// The file of the data directory the history is appended to, one json record
+/// per line.
+pub const HISTORY_FILE: &str = "history.jsonl";
+/// A created project, with the answers of its form to create it again.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub(crate) project_type: ProjectType,
+    /// The label and value of every field, as the form showed them.
+    pub(crate) values:       Vec<(String, String)>,
+    pub(crate) path:         PathBuf,
+    pub(crate) created_at:   DateTime<Local>,
+    /// The version of every tool found when the project was created.
+    pub(crate) tools:        BTreeMap<String, String>,
+}
+impl Record {
+    pub(crate) fn new(
+        context: &AppContext,
+        project_type: ProjectType,
+        values: Vec<(String, String)>,
+        path: PathBuf,
+    ) -> Self {
+        let executables = context.executables();
+        Self {
+            project_type,
+            values,
+            path,
+            created_at: Local::now(),
+            tools: Executable::tools()
+                .into_iter()
+                .filter_map(|name| {
+                    let version = Version::installed(executables, &name)?;
+                    Some((name, version.to_string()))
+                })
+                .collect(),
+        }
+    }
+
+    /// The directory name of the project.
+    pub(crate) fn name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .display()
+            .to_string()
+    }
+
+    pub(crate) fn created_at(&self) -> String {
+        self.created_at.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+/// The projects created so far, oldest first.
+#[derive(Default)]
+pub struct History {
+    pub(crate) records: Vec<Record>,
+    /// Why the lines which are not records were skipped, like a truncated
+    /// append or a record of another version.
+    pub(crate) skipped: Vec<String>,
+}
+impl History {
+    fn path(context: &AppContext) -> PathBuf {
+        context.config.data_dir.join(HISTORY_FILE)
+    }
+
+    /// Reads the history file of the data directory, empty until a project is
+    /// created.
+    ///
+    /// # Errors
+    /// If the file cannot be read.
+    pub fn load(context: &AppContext) -> Result<Self> {
+        Self::read(&Self::path(context))
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+        let mut history = Self::default();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(record) => history.records.push(record),
+                Err(error) => history.skipped.push(format!(
+                    "line {} of {}: {error}",
+                    index + 1,
+                    path.display()
+                )),
+            }
+        }
+        Ok(history)
+    }
+
+    /// Appends `record` to the history file, creating the data directory if
+    /// needed.
+    pub(crate) fn append(context: &AppContext, record: &Record) -> Result<()> {
+        let path = Self::path(context);
+        fs::create_dir_all(&context.config.data_dir)
+            .with_context(|| format!("cannot create {}", context.config.data_dir.display()))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("cannot open {}", path.display()))?;
+        // 上次追加被截断时, 另起一行, 以免连同这条记录一起读不出来
+        let mut last = [b'\n'];
+        if file.metadata()?.len() > 0 {
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+        }
+        let separator = if last[0] == b'\n' { "" } else { "\n" };
+        writeln!(file, "{separator}{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// # Errors
+    /// If the records cannot be serialized to json.
+    pub fn print(&self, json: bool) -> Result<()> {
+        for skipped in &self.skipped {
+            eprintln!("skipped {skipped}");
+        }
+        if json {
+            println!("{}", serde_json::to_string_pretty(&self.records)?);
+            return Ok(());
+        }
+        if self.records.is_empty() {
+            println!("No project created yet");
+            return Ok(());
+        }
+        let rows: Vec<[String; 4]> = self
+            .records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                [
+                    (index + 1).to_string(),
+                    record.created_at(),
+                    record.project_type.to_string(),
+                    record.path.display().to_string(),
+                ]
+            })
+            .collect();
+        print_table(["#", "CREATED", "TYPE", "PATH"], &rows);
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod history_test {
+    use super::{History, Record};
+    use crate::{AppContext, Config, common::ProjectType};
+    use std::{env, fs, process};
+    #[test]
+    fn test_append_and_load() {
+        let data_dir = env::temp_dir().join(format!("project-setup-history-{}", process::id()));
+        let context = AppContext::default().with_config(Config {
+            data_dir: data_dir.clone(),
+            ..Config::default()
+        });
+        assert!(History::load(&context).unwrap().records.is_empty());
+        for name in ["first", "second"] {
+            let record = Record::new(
+                &context,
+                ProjectType::Cargo,
+                vec![("name".to_string(), name.to_string())],
+                data_dir.join(name),
+            );
+            History::append(&context, &record).unwrap();
+        }
+        let records = History::load(&context).unwrap().records;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].project_type, ProjectType::Cargo);
+        assert_eq!(records[1].name(), "second");
+        assert_eq!(
+            records[0].values,
+            [("name".to_string(), "first".to_string())]
+        );
+        let mut contents = fs::read_to_string(data_dir.join(super::HISTORY_FILE)).unwrap();
+        contents.insert_str(0, "{}\n");
+        contents.push_str("{\"project_type\":\"Cargo\"");
+        fs::write(data_dir.join(super::HISTORY_FILE), contents).unwrap();
+        let record = Record::new(&context, ProjectType::Cmake, vec![], data_dir.join("third"));
+        History::append(&context, &record).unwrap();
+        let history = History::load(&context).unwrap();
+        assert_eq!(history.records.len(), 3);
+        assert_eq!(history.records[2].name(), "third");
+        assert_eq!(history.skipped.len(), 2);
+        assert!(history.skipped[0].starts_with("line 1 of "));
+        fs::remove_dir_all(data_dir).unwrap();
+    }
+}
@@ -2,38 +2,95 @@ pub mod app {
     pub mod application;
     mod cargo;
     mod cmake;
+    mod confirmation;
+    mod creation;
+    mod dir_browser;
+    mod doctor;
+    mod form;
+    mod form_layout;
     mod inner;
     mod prepare;
+    mod preview;
     mod radio_option;
+    mod recent;
     mod springboot;
+    mod template;
+    mod text_input;
     mod wip;
     pub use application::Application;
     use cargo::CargoInner;
     use cmake::CmakeInner;
+    use confirmation::{Confirmation, ConfirmationOutput};
+    use creation::{Creation, CreationOutput};
+    use dir_browser::{DirBrowser, DirBrowserOutput};
+    pub use doctor::Doctor;
+    use form::{render_form, render_inner_form};
+    use form_layout::form_layout;
     use inner::{
-        Inner, InnerCommonState, InnerField, InnerFieldMapping, InnerHandleKeyEventOutput,
-        InnerState, InnerTipLabel, handle_inner_keyevent,
+        DerivedFields, FormFields, Inner, InnerCommonState, InnerField, InnerFieldMapping,
+        InnerHandleKeyEventOutput, InnerState, InnerTipLabel, form_restore, form_summary,
+        handle_form_keyevent, handle_form_paste, handle_inner_keyevent, handle_inner_paste,
+        inner_restore, inner_summary,
     };
     pub use prepare::PrepareApplication;
-    pub(crate) use prepare::{PreparePermit, PrepareRecv, PrepareTrait};
+    use prepare::PrepareProgress;
+    pub(crate) use prepare::{PrepareRecv, PrepareTrait};
+    use preview::Preview;
     pub(crate) use radio_option::RadioOptionValue;
-    use radio_option::{RadioOption, RadioOptionTrait};
+    use radio_option::{Choice, RadioOption, RadioOptionTrait};
+    use recent::{Recent, RecentOutput};
     use springboot::SpringBootInner;
+    use template::TemplateInner;
+    use text_input::TextInput;
     use wip::WipInner;
 }
 pub mod args;
 pub use args::Args;
+pub mod context;
+pub use context::{AppContext, Config};
+pub mod history;
+pub mod generate {
+    pub mod cargo;
+    pub mod cmake;
+    mod engine;
+    mod generation;
+    pub mod import;
+    pub mod springboot;
+    pub mod template;
+    use crate::app::RadioOptionValue;
+    pub use crate::common::{Editor, Vcs};
+    pub use cargo::CargoOptions;
+    pub use cmake::CmakeOptions;
+    pub use engine::TemplateValue;
+    pub(crate) use engine::Variables;
+    pub(crate) use generation::{Generation, GenerationEvent, generate_logged, generate_with};
+    pub use generation::{Options, Report, dry_run, generate};
+    pub use springboot::SpringBootOptions;
+    pub use template::TemplateOptions;
+}
+pub use generate::generate;
 pub(crate) mod common {
     mod editor;
+    mod editor_settings;
     mod executable;
     mod loop_number;
+    mod path;
+    mod project_packaging;
     mod project_type;
+    mod table;
     mod vcs;
-    pub(crate) use editor::Editor;
-    pub(crate) use executable::{Executable, ExecutableEnumTrait};
+    mod version;
+    pub use editor::Editor;
+    pub(crate) use editor_settings::{EditorProject, EditorSettings};
+    pub(crate) use executable::{Executable, ExecutableEnumTrait, ExecutableMap};
     pub(crate) use loop_number::LoopNumber;
+    pub(crate) use path::{
+        complete_directory, expand_path, subdirectories, validate_directory, writable_directory,
+    };
     pub(crate) use project_type::ProjectType;
-    pub(crate) use vcs::Vcs;
+    pub(crate) use table::print_table;
+    pub use vcs::Vcs;
+    pub(crate) use version::{Requirement, Version};
 }
 pub(crate) mod features {
     mod download;
@@ -41,9 +98,7 @@ pub(crate) mod features {
     pub(crate) use download::{RequestMethod, download_file};
     pub(crate) use zip::unzip;
 }
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
-use project_setup_derive::{EnumFunc, InnerState, LoopableNumberedEnum, RadioOption};
+use project_setup_derive::{EnumFunc, InnerForm, InnerState, LoopableNumberedEnum, RadioOption};
 pub trait LoopableNumberedEnum {
     fn num(&self) -> usize;
     fn next_index(&self) -> usize;
@@ -54,19 +109,46 @@ pub trait LoopableNumberedEnum {
     fn prev(&self) -> Self;
 }
 #[allow(dead_code)]
-#[derive(Debug, Clone, LoopableNumberedEnum, FromPrimitive, ToPrimitive, PartialEq)]
-#[numbered_enum(loop_within = 3)]
+#[derive(Debug, Clone, LoopableNumberedEnum, PartialEq)]
 enum LoopableNumbered {
     One,
     Two,
     Three,
 }
+#[allow(dead_code)]
+#[derive(Debug, Clone, LoopableNumberedEnum, PartialEq)]
+enum LoopableGapped {
+    One = 1,
+    #[numbered_enum(skip)]
+    Two,
+    Five = 5,
+    Nine = 9,
+}
+#[cfg(test)]
+const _: () = {
+    assert!(matches!(
+        LoopableNumbered::Three.next(),
+        LoopableNumbered::One
+    ));
+    assert!(matches!(
+        LoopableNumbered::One.prev(),
+        LoopableNumbered::Three
+    ));
+    assert!(matches!(LoopableGapped::One.next(), LoopableGapped::Five));
+    assert!(matches!(LoopableGapped::Two.next(), LoopableGapped::Five));
+    assert!(matches!(LoopableGapped::Two.prev(), LoopableGapped::One));
+    assert!(matches!(LoopableGapped::Nine.next(), LoopableGapped::One));
+    assert!(matches!(LoopableGapped::One.prev(), LoopableGapped::Nine));
+};
 #[cfg(test)]
 mod project_setup_derive_test {
-    use crate::LoopableNumbered;
+    use crate::{LoopableGapped, LoopableNumbered};
     #[test]
     fn test_numbered_enum_extra_method() {
         assert_eq!(LoopableNumbered::One.next(), LoopableNumbered::Two);
         assert_eq!(LoopableNumbered::One.prev(), LoopableNumbered::Three);
+        assert_eq!(LoopableGapped::Five.num(), 2);
+        assert_eq!(LoopableGapped::Nine.next_index(), 0);
+        assert_eq!(LoopableGapped::One.prev_index(), 3);
     }
 }
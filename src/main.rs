@@ -1,25 +1,37 @@
 use anyhow::Result;
 use clap::Parser;
 use project_setup::{
-    app::{Application, PrepareApplication},
-    args::Args,
+    AppContext,
+    app::{Application, Doctor, PrepareApplication},
+    args::{Args, Command},
+    history::History,
 };
+use ratatui::crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
+    execute,
+};
+use std::{io::stdout, process, sync::Arc};
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    let context = Arc::new(AppContext::new(args));
+    match args.command {
+        Some(Command::Doctor { json }) => return Doctor::check(context).await.print(json),
+        Some(Command::History { json }) => return History::load(&context)?.print(json),
+        None => {}
+    }
     let res = ratatui::run(|terminal| {
-        PrepareApplication::default().run(terminal)?;
-        Application::new(args).run(terminal)
+        // 粘贴的内容作为一个事件整体到达, 而不是逐个按键
+        execute!(stdout(), EnableBracketedPaste)?;
+        PrepareApplication::new(context.clone()).run(terminal)?;
+        let res = Application::new(context).run(terminal);
+        execute!(stdout(), DisableBracketedPaste)?;
+        res
     });
+    // 终端已经恢复, 错误输出到 stderr
     if let Err(err) = res {
-        println!("{err:?}");
+        eprintln!("{err:#}");
+        process::exit(1);
     }
     Ok(())
 }
-#[cfg(test)]
-mod hello {
-    #[test]
-    fn world() {
-        println!("hello world");
-    }
-}
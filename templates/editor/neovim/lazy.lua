@@ -0,0 +1,2 @@
+-- Project local plugin specs for lazy.nvim.
+return {}
@@ -0,0 +1,39 @@
+-- Project local config, loaded when `exrc` is enabled.
+local ok, dap = pcall(require, "dap")
+if ok then
+{% if project == "cargo" %}
+  dap.configurations.rust = {
+    {
+      name = "Debug {{ name }}",
+      type = "codelldb",
+      request = "launch",
+{% if library %}
+      program = function() return vim.fn.input("Test binary: ", "target/debug/deps/", "file") end,
+{% else %}
+      program = "${workspaceFolder}/target/debug/{{ name }}",
+{% endif %}
+      cwd = "${workspaceFolder}",
+    },
+  }
+{% elif project == "cmake" %}
+  dap.configurations.{% if cpp %}cpp{% else %}c{% endif %} = {
+    {
+      name = "Debug {{ name }}",
+      type = "codelldb",
+      request = "launch",
+      program = "${workspaceFolder}/build/{{ name }}",
+      cwd = "${workspaceFolder}",
+    },
+  }
+{% elif project == "springboot" %}
+  dap.configurations.java = {
+    {
+      name = "Debug {{ name }}",
+      type = "java",
+      request = "launch",
+      mainClass = "{{ main_class }}",
+      projectName = "{{ name }}",
+    },
+  }
+{% endif %}
+end